@@ -16,6 +16,10 @@
 //!
 //! See [wikipedia] for more information.
 //!
+//! This module also provides the revolving-door order (see [`encode_revolving`]) in which
+//! consecutive k-combinations differ by swapping one element, and its loopless iterator
+//! [`RevolvingIter`].
+//!
 //! [wikipedia]: https://en.wikipedia.org/wiki/Combinatorial_number_system
 
 #[cfg(feature = "alloc")]
@@ -23,6 +27,9 @@ use alloc::vec;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use core::borrow::BorrowMut;
+use core::ops::Range;
+
+use crate::iter::{Lending, LendingBack, RankedIter};
 
 /// Writes the combination of a value to a slice.
 ///
@@ -46,17 +53,16 @@ pub fn decode_mut(mut n: usize, mut k: usize, r: &mut [usize]) {
     debug_assert_eq!(r.len(), k, "Failed precondition");
     debug_assert!(k > 0 || n == 0, "Failed precondition");
     while k > 0 {
+        // The binomial coefficients are multiplied in u128 to avoid intermediate overflows.
         let mut i = k;
-        let mut x = 1;
-        while x <= n {
+        let mut x = 1u128;
+        while x <= n as u128 {
             i += 1;
-            x *= i;
-            x /= i - k;
+            x = x * i as u128 / (i - k) as u128;
         }
-        x *= i - k;
-        x /= i;
+        x = x * (i - k) as u128 / i as u128;
         i -= 1;
-        n -= x;
+        n -= x as usize;
         k -= 1;
         r[k] = i;
     }
@@ -117,10 +123,66 @@ fn decode_ok() {
     test(6, 3, &[1, 2, 4]);
     test(7, 3, &[0, 3, 4]);
     test(8, 3, &[1, 3, 4]);
+    test(crate::combination(63, 31) - 1, 31, &(32 .. 63).collect::<Vec<_>>());
     test(9, 3, &[2, 3, 4]);
     test(10, 3, &[0, 1, 5]);
 }
 
+/// Writes the k-combinations of the values in `range` to `out` with `layout`.
+///
+/// This is faster than calling [`decode_mut`] for each value: only the first value is decoded, the
+/// next k-combinations are obtained by stepping.
+///
+/// # Panics
+///
+/// Panics in debug mode if `range.end > 1 && k == 0` or `out.len() != k * range.len()`. Without
+/// the `alloc` feature, panics if `layout` is [`ColumnMajor`](crate::Layout::ColumnMajor) and
+/// `k > 64`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::combinadics::decode_range_into;
+/// # use number_encoding::Layout;
+/// let mut out = [0; 6];
+/// decode_range_into(2 .. 5, 2, Layout::RowMajor, &mut out);
+/// assert_eq!(out, [1, 2, 0, 3, 1, 3]);
+/// decode_range_into(2 .. 5, 2, Layout::ColumnMajor, &mut out);
+/// assert_eq!(out, [1, 0, 1, 2, 3, 3]);
+/// ```
+pub fn decode_range_into(range: Range<usize>, k: usize, layout: crate::Layout, out: &mut [usize]) {
+    debug_assert!(k > 0 || range.end <= 1, "Failed precondition");
+    debug_assert_eq!(out.len(), k * range.len(), "Failed precondition");
+    let init = |xs: &mut [usize]| decode_mut(range.start, k, xs);
+    crate::write_range(k, range.len(), layout, out, init, step);
+}
+
+#[test]
+fn decode_range_into_ok() {
+    for layout in [crate::Layout::RowMajor, crate::Layout::ColumnMajor] {
+        for k in 0 .. 5 {
+            for start in 0 .. 20 {
+                for len in 0 .. 20 {
+                    if k == 0 && start + len > 1 {
+                        continue;
+                    }
+                    let mut out = vec![0; k * len];
+                    decode_range_into(start .. start + len, k, layout, &mut out);
+                    for i in 0 .. len {
+                        let xs: Vec<_> = match layout {
+                            crate::Layout::RowMajor => out[i * k .. (i + 1) * k].to_vec(),
+                            crate::Layout::ColumnMajor => {
+                                (0 .. k).map(|j| out[j * len + i]).collect()
+                            }
+                        };
+                        assert_eq!(xs, decode(start + i, k), "k={k} start={start} i={i}");
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Returns the value of a combination.
 ///
 /// The returned value can be decoded with [`decode`] to get back `xs`.
@@ -182,6 +244,127 @@ fn encode_ok() {
     test(&[0, 1, 5], 10);
 }
 
+/// Advances an increasing slice to the next combination without bound.
+fn step(xs: &mut [usize]) {
+    let k = xs.len();
+    for i in 0 .. k {
+        xs[i] += 1;
+        if i == k - 1 || xs[i] < xs[i + 1] {
+            break;
+        }
+        xs[i] = i;
+    }
+}
+
+/// Moves an increasing slice back to the previous combination.
+///
+/// The slice must not be the first combination.
+fn step_back(xs: &mut [usize]) {
+    let i = xs.iter().enumerate().position(|(i, &x)| x != i).unwrap();
+    xs[i] -= 1;
+    for j in 0 .. i {
+        xs[j] = xs[i] - (i - j);
+    }
+}
+
+/// Rearranges a k-combination of a set of `n` elements to the next one in value order.
+///
+/// Returns `false` if the k-combination is the last one (i.e. `[n - k, .., n - 1]`), in which case
+/// it is rearranged to the first one (i.e. `[0, .., k - 1]`).
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or has elements greater or equal to `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::combinadics::next_combination;
+/// let mut xs = [1, 2, 3];
+/// assert!(next_combination(&mut xs, 5));
+/// assert_eq!(xs, [0, 1, 4]);
+/// let mut xs = [2, 3, 4];
+/// assert!(!next_combination(&mut xs, 5));
+/// assert_eq!(xs, [0, 1, 2]);
+/// ```
+pub fn next_combination(xs: &mut [usize], n: usize) -> bool {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    debug_assert!(xs.last().is_none_or(|&x| x < n), "Failed precondition");
+    let k = xs.len();
+    if xs.first().is_none_or(|&x| x == n - k) {
+        for (i, x) in xs.iter_mut().enumerate() {
+            *x = i;
+        }
+        return false;
+    }
+    step(xs);
+    true
+}
+
+#[test]
+fn next_combination_ok() {
+    for n in 0 .. 7 {
+        for k in 0 ..= n {
+            let mut xs: Vec<usize> = (0 .. k).collect();
+            for p in 1 .. crate::combination(n, k) {
+                assert!(next_combination(&mut xs, n), "n={n} k={k} p={p}");
+                assert_eq!(encode(&xs), p, "n={n} k={k} p={p}");
+            }
+            assert!(!next_combination(&mut xs, n), "n={n} k={k}");
+            assert_eq!(encode(&xs), 0, "n={n} k={k}");
+        }
+    }
+}
+
+/// Rearranges a k-combination of a set of `n` elements to the previous one in value order.
+///
+/// Returns `false` if the k-combination is the first one (i.e. `[0, .., k - 1]`), in which case it
+/// is rearranged to the last one (i.e. `[n - k, .., n - 1]`).
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or has elements greater or equal to `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::combinadics::prev_combination;
+/// let mut xs = [0, 1, 4];
+/// assert!(prev_combination(&mut xs, 5));
+/// assert_eq!(xs, [1, 2, 3]);
+/// let mut xs = [0, 1, 2];
+/// assert!(!prev_combination(&mut xs, 5));
+/// assert_eq!(xs, [2, 3, 4]);
+/// ```
+pub fn prev_combination(xs: &mut [usize], n: usize) -> bool {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    debug_assert!(xs.last().is_none_or(|&x| x < n), "Failed precondition");
+    let k = xs.len();
+    if xs.last().is_none_or(|&x| x == k - 1) {
+        for (i, x) in xs.iter_mut().enumerate() {
+            *x = n - k + i;
+        }
+        return false;
+    }
+    step_back(xs);
+    true
+}
+
+#[test]
+fn prev_combination_ok() {
+    for n in 0 .. 7 {
+        for k in 0 ..= n {
+            let mut xs: Vec<usize> = (n - k .. n).collect();
+            for p in (0 .. crate::combination(n, k) - 1).rev() {
+                assert!(prev_combination(&mut xs, n), "n={n} k={k} p={p}");
+                assert_eq!(encode(&xs), p, "n={n} k={k} p={p}");
+            }
+            assert!(!prev_combination(&mut xs, n), "n={n} k={k}");
+            assert_eq!(encode(&xs), crate::combination(n, k) - 1, "n={n} k={k}");
+        }
+    }
+}
+
 /// Iterates over all k-combinations.
 ///
 /// The k-combinations are iterated in value order:
@@ -215,6 +398,22 @@ fn encode_ok() {
 /// }
 /// ```
 ///
+/// The iteration can be bounded to the k-combinations of a set of `n` elements, in which case the
+/// number of remaining k-combinations is tracked (unless it overflows, see [`Lending`] for an
+/// iteration that always ends):
+///
+/// ```rust
+/// # use number_encoding::combinadics::Iter;
+/// # fn process(xs: &[usize]) {}
+/// # let n = 5;
+/// # let k = 3;
+/// let mut iter = Iter::new_bounded(n, k);
+/// while iter.remaining() != Some(0) {
+///     process(iter.get());
+///     iter.advance();
+/// }
+/// ```
+///
 /// In a no-std environment, you can pass a buffer of size `K`:
 ///
 /// ```rust
@@ -223,15 +422,43 @@ fn encode_ok() {
 /// let mut buffer = [0usize; K];
 /// let mut iter = Iter::new_with_buffer(&mut buffer[..]);
 /// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
 pub struct Iter<T: BorrowMut<[usize]>> {
     data: T,
+    lent: bool,
+    rank: usize,
+    end: Option<usize>,
+    n: Option<usize>,
 }
 
 #[cfg(feature = "alloc")]
 impl Iter<Vec<usize>> {
     /// Constructs an iterator.
     pub fn new(k: usize) -> Iter<Vec<usize>> {
-        Iter { data: (0 .. k).collect() }
+        Iter::new_with_buffer(vec![0; k])
+    }
+
+    /// Constructs an iterator over the k-combinations of a set of `n` elements.
+    pub fn new_bounded(n: usize, k: usize) -> Iter<Vec<usize>> {
+        Iter::new_with_buffer_bounded(vec![0; k], n)
+    }
+
+    /// Constructs an iterator over the k-combinations with value in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `range.start > range.end`.
+    pub fn new_range(k: usize, range: Range<usize>) -> Iter<Vec<usize>> {
+        Iter::new_with_buffer_range(vec![0; k], range)
+    }
+
+    /// Constructs an iterator starting from the k-combination of a given value.
+    pub fn new_at_rank(k: usize, rank: usize) -> Iter<Vec<usize>> {
+        let mut iter = Iter::new(k);
+        iter.seek(rank);
+        iter
     }
 }
 
@@ -241,7 +468,30 @@ impl<T: BorrowMut<[usize]>> Iter<T> {
         for (i, x) in buffer.borrow_mut().iter_mut().enumerate() {
             *x = i;
         }
-        Iter { data: buffer }
+        Iter { data: buffer, lent: false, rank: 0, end: None, n: None }
+    }
+
+    /// Constructs an iterator over the k-combinations of a set of `n` elements with a buffer.
+    pub fn new_with_buffer_bounded(buffer: T, n: usize) -> Iter<T> {
+        let k = buffer.borrow().len();
+        let mut iter = Iter::new_with_buffer(buffer);
+        // The end is only tracked for reporting. The last k-combination ends the iteration.
+        iter.end = crate::checked_combination(n, k);
+        iter.n = Some(n);
+        iter
+    }
+
+    /// Constructs an iterator over the k-combinations with value in `range` with a buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `range.start > range.end`.
+    pub fn new_with_buffer_range(buffer: T, range: Range<usize>) -> Iter<T> {
+        debug_assert!(range.start <= range.end, "Failed precondition");
+        let mut iter = Iter::new_with_buffer(buffer);
+        iter.seek(range.start);
+        iter.end = Some(range.end);
+        iter
     }
 
     /// Constructs an iterator starting from a given k-combination.
@@ -251,7 +501,8 @@ impl<T: BorrowMut<[usize]>> Iter<T> {
     /// Panics in debug mode if `xs` is not increasing.
     pub fn new_from(xs: T) -> Iter<T> {
         debug_assert!(crate::is_ordered_set(xs.borrow()), "Failed precondition");
-        Iter { data: xs }
+        let rank = encode(xs.borrow());
+        Iter { data: xs, lent: false, rank, end: None, n: None }
     }
 
     /// Returns the current combination.
@@ -259,16 +510,110 @@ impl<T: BorrowMut<[usize]>> Iter<T> {
         self.data.borrow()
     }
 
+    /// Returns the value of the current combination.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the combination of a given value.
+    ///
+    /// The end of the iteration (if any) is not modified.
+    pub fn seek(&mut self, rank: usize) {
+        let xs = self.data.borrow_mut();
+        decode_mut(rank, xs.len(), xs);
+        self.lent = false;
+        self.rank = rank;
+    }
+
+    /// Returns the number of combinations from the current one to the end of the iteration.
+    ///
+    /// Returns `None` if the iteration is unbounded (or the number overflows).
+    pub fn remaining(&self) -> Option<usize> {
+        self.end.map(|end| end.saturating_sub(self.rank))
+    }
+
+    /// Returns whether the current combination is the last one of a bounded iteration.
+    fn is_last(&self) -> bool {
+        let xs = self.data.borrow();
+        self.n.is_some_and(|n| xs.first().is_none_or(|&x| x + xs.len() == n))
+    }
+
     /// Advances to the next combination.
     pub fn advance(&mut self) {
-        let k = self.data.borrow().len();
-        for i in 0 .. k {
-            self.data.borrow_mut()[i] += 1;
-            if i == k - 1 || self.data.borrow()[i] < self.data.borrow()[i + 1] {
-                break;
+        self.rank += 1;
+        step(self.data.borrow_mut());
+    }
+
+    /// Moves back to the previous combination.
+    ///
+    /// This is the reverse of [`advance`](Iter::advance).
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if the current combination is the first one.
+    pub fn retreat(&mut self) {
+        debug_assert!(self.rank > 0, "Failed precondition");
+        self.rank -= 1;
+        step_back(self.data.borrow_mut());
+    }
+}
+
+/// Lends the current combination, then advances before each following call.
+///
+/// This iteration ends when there are no [remaining](Iter::remaining) combinations, or after the
+/// last combination of a set of `n` elements for [`new_bounded`](Iter::new_bounded) (even if their
+/// number overflows).
+impl<T: BorrowMut<[usize]>> Lending for Iter<T> {
+    type Item = usize;
+
+    fn lend(&mut self) -> Option<&[usize]> {
+        if self.lent {
+            if self.is_last() {
+                return None;
             }
-            self.data.borrow_mut()[i] = i;
+            self.advance();
+        }
+        if self.remaining() == Some(0) || self.n.is_some_and(|n| self.get().len() > n) {
+            return None;
         }
+        self.lent = true;
+        Some(self.get())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining() {
+            None => (usize::MAX, None),
+            Some(remaining) => {
+                let remaining = remaining.saturating_sub(self.lent as usize);
+                (remaining, Some(remaining))
+            }
+        }
+    }
+}
+
+/// Lends the current combination, then retreats before each following call.
+///
+/// This iteration ends after the first combination.
+impl<T: BorrowMut<[usize]>> LendingBack for Iter<T> {
+    fn lend_back(&mut self) -> Option<&[usize]> {
+        if self.lent {
+            if self.rank == 0 {
+                return None;
+            }
+            self.retreat();
+        }
+        self.lent = true;
+        Some(self.get())
+    }
+}
+
+impl<T: BorrowMut<[usize]>> RankedIter for Iter<T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
     }
 }
 
@@ -333,3 +678,460 @@ fn iter_new_from_ok() {
         ],
     );
 }
+
+#[test]
+fn iter_bounded_ok() {
+    fn test(n: usize, k: usize) {
+        let mut iter = Iter::new_bounded(n, k);
+        for i in 0 .. crate::combination(n, k) {
+            assert_eq!(iter.remaining(), Some(crate::combination(n, k) - i), "n={n} k={k}");
+            assert_eq!(encode(iter.get()), i, "n={n} k={k}");
+            assert!(iter.get().iter().all(|&x| x < n), "n={n} k={k}");
+            iter.advance();
+        }
+        assert_eq!(iter.remaining(), Some(0), "n={n} k={k}");
+        let r: Vec<_> = Iter::new_bounded(n, k).vecs().collect();
+        assert_eq!(r.len(), crate::combination(n, k), "n={n} k={k}");
+    }
+    for n in 0 .. 8 {
+        for k in 0 .. 8 {
+            test(n, k);
+        }
+    }
+    assert_eq!(Iter::new_bounded(100, 50).remaining(), None);
+    // The last combination ends the iteration even if the number of combinations overflows.
+    let mut iter = Iter::new_bounded(100, 50);
+    let last: Vec<_> = (50 .. 100).collect();
+    iter.data = last.clone();
+    assert_eq!(iter.lend(), Some(&last[..]));
+    assert_eq!(iter.lend(), None);
+    assert_eq!(iter.lend(), None);
+}
+
+#[test]
+fn iter_range_ok() {
+    fn test(k: usize, range: Range<usize>) {
+        let r: Vec<_> = Iter::new_range(k, range.clone()).vecs().ranked().collect();
+        assert_eq!(r.len(), range.len(), "k={k} range={range:?}");
+        for (i, (p, xs)) in range.clone().zip(r) {
+            assert_eq!(p, i, "k={k} range={range:?}");
+            assert_eq!(xs, decode(i, k), "k={k} range={range:?}");
+        }
+    }
+    test(0, 0 .. 0);
+    test(0, 0 .. 1);
+    test(3, 0 .. 10);
+    test(3, 4 .. 12);
+    test(3, 7 .. 7);
+    let mut iter = Iter::new_range(2, 1 .. 4).vecs();
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert_eq!(iter.next(), Some(vec![0, 2]));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    assert_eq!(iter.nth(1), Some(vec![0, 3]));
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn iter_rank_ok() {
+    let mut iter = Iter::new_from(vec![0, 2, 4]);
+    for i in 5 .. 20 {
+        assert_eq!(iter.rank(), i);
+        assert_eq!(encode(iter.get()), i);
+        iter.advance();
+    }
+    iter.seek(3);
+    assert_eq!(iter.get(), &[1, 2, 3]);
+    assert_eq!(iter.rank(), 3);
+    let mut iter = Iter::new_range(3, 2 .. 6);
+    assert_eq!(iter.lend(), Some(&[0, 2, 3][..]));
+    iter.seek(4);
+    assert_eq!(iter.remaining(), Some(2));
+    assert_eq!(iter.vecs().collect::<Vec<_>>(), [[0, 1, 4], [0, 2, 4]]);
+    assert_eq!(Iter::new_at_rank(2, 5).get(), &[2, 3]);
+}
+
+#[test]
+fn iter_retreat_ok() {
+    let mut iter = Iter::new_at_rank(3, 20);
+    for i in (0 .. 20).rev() {
+        iter.retreat();
+        assert_eq!(iter.rank(), i);
+        assert_eq!(encode(iter.get()), i);
+    }
+}
+
+/// Writes the combination of a value in revolving-door order to a slice.
+///
+/// The written combination can be encoded with [`encode_revolving`] to get back `n`.
+///
+/// ```rust
+/// # use number_encoding::combinadics::{decode_revolving_mut, encode_revolving};
+/// # let n = 5;
+/// # let k = 3;
+/// let mut xs = vec![0; k];
+/// decode_revolving_mut(n, k, &mut xs);
+/// assert_eq!(encode_revolving(&xs), n);
+/// ```
+///
+/// See [`decode_revolving`] for a version that allocates a vector for the combination.
+///
+/// # Panics
+///
+/// Panics in debug mode if `n > 0 && k == 0`.
+pub fn decode_revolving_mut(mut n: usize, k: usize, r: &mut [usize]) {
+    debug_assert_eq!(r.len(), k, "Failed precondition");
+    debug_assert!(k > 0 || n == 0, "Failed precondition");
+    if k == 0 {
+        return;
+    }
+    let mut x = k - 1;
+    while crate::combination(x + 1, k) <= n {
+        x += 1;
+    }
+    for i in (1 ..= k).rev() {
+        while crate::combination(x, i) > n {
+            x -= 1;
+        }
+        r[i - 1] = x;
+        n = crate::combination(x, i - 1) - 1 - (n - crate::combination(x, i));
+        x = x.saturating_sub(1);
+    }
+}
+
+/// Returns the combination of a value in revolving-door order.
+///
+/// The returned combination can be encoded with [`encode_revolving`] to get back `n`.
+///
+/// ```rust
+/// # use number_encoding::combinadics::{decode_revolving, encode_revolving};
+/// # let n = 5;
+/// # let k = 3;
+/// let xs = decode_revolving(n, k);
+/// assert_eq!(encode_revolving(&xs), n);
+/// ```
+///
+/// See [`decode_revolving_mut`] for a version that writes the combination to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `n > 0 && k == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::combinadics::decode_revolving;
+/// assert_eq!(decode_revolving(0, 2), &[0, 1]);
+/// assert_eq!(decode_revolving(1, 2), &[1, 2]);
+/// assert_eq!(decode_revolving(2, 2), &[0, 2]);
+/// assert_eq!(decode_revolving(3, 2), &[2, 3]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_revolving(n: usize, k: usize) -> Vec<usize> {
+    let mut r = vec![0; k];
+    decode_revolving_mut(n, k, &mut r);
+    r
+}
+
+#[test]
+fn decode_revolving_ok() {
+    fn test(n: usize, k: usize, r: &[usize]) {
+        assert_eq!(decode_revolving(n, k), r, "n={n} k={k}");
+    }
+    test(0, 0, &[]);
+    test(0, 1, &[0]);
+    test(1, 1, &[1]);
+    test(2, 1, &[2]);
+    test(0, 2, &[0, 1]);
+    test(1, 2, &[1, 2]);
+    test(2, 2, &[0, 2]);
+    test(3, 2, &[2, 3]);
+    test(4, 2, &[1, 3]);
+    test(5, 2, &[0, 3]);
+    test(0, 3, &[0, 1, 2]);
+    test(1, 3, &[0, 2, 3]);
+    test(2, 3, &[1, 2, 3]);
+    test(3, 3, &[0, 1, 3]);
+    test(4, 3, &[0, 3, 4]);
+    test(5, 3, &[1, 3, 4]);
+    test(6, 3, &[2, 3, 4]);
+    test(7, 3, &[0, 2, 4]);
+    test(8, 3, &[1, 2, 4]);
+    test(9, 3, &[0, 1, 4]);
+}
+
+/// Returns the value of a combination in revolving-door order.
+///
+/// The returned value can be decoded with [`decode_revolving`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::combinadics::{decode_revolving, encode_revolving};
+/// # let xs = &[0, 2, 4];
+/// let k = xs.len();
+/// let n = encode_revolving(xs);
+/// assert_eq!(decode_revolving(n, k), xs);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::combinadics::encode_revolving;
+/// assert_eq!(encode_revolving(&[0, 1, 2]), 0);
+/// assert_eq!(encode_revolving(&[0, 2, 3]), 1);
+/// assert_eq!(encode_revolving(&[1, 2, 3]), 2);
+/// assert_eq!(encode_revolving(&[0, 1, 3]), 3);
+/// assert_eq!(encode_revolving(&[0, 3, 4]), 4);
+/// ```
+pub fn encode_revolving(xs: &[usize]) -> usize {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    let mut r = 0;
+    for (i, &x) in xs.iter().enumerate() {
+        r = crate::combination(x, i) - 1 - r + crate::combination(x, i + 1);
+    }
+    r
+}
+
+#[test]
+fn encode_revolving_ok() {
+    for k in 0 .. 5 {
+        let count = if k == 0 { 1 } else { 100 };
+        for n in 0 .. count {
+            assert_eq!(encode_revolving(&decode_revolving(n, k)), n, "n={n} k={k}");
+        }
+    }
+}
+
+/// Advances a k-combination of a set of `n` elements in revolving-door order.
+///
+/// The `prefix` is the length of the longest prefix of the form `[0, 1, ..]`. It is updated in
+/// constant time and permits to find the elements to swap without looping over the combination.
+///
+/// Returns the element that left and the element that entered, or `None` if the k-combination is
+/// the last one (in which case it is not modified).
+fn step_revolving(xs: &mut [usize], n: usize, prefix: &mut usize) -> Option<(usize, usize)> {
+    // This is algorithm R of Knuth (TAOCP 7.2.1.3) with 1-based indices for c_j = xs[j - 1].
+    let k = xs.len();
+    let next = |xs: &[usize], j: usize| if j < k { xs[j] } else { n };
+    if k == 0 {
+        return None;
+    }
+    if k % 2 == 1 {
+        if xs[0] + 1 < next(xs, 1) {
+            xs[0] += 1;
+            *prefix = 0;
+            return Some((xs[0] - 1, xs[0]));
+        }
+    } else if xs[0] > 0 {
+        xs[0] -= 1;
+        *prefix = (xs[0] == 0) as usize;
+        return Some((xs[0] + 1, xs[0]));
+    }
+    // All steps below the prefix fail: at most 3 steps remain.
+    let start = (*prefix).max(2);
+    for j in start ..= k {
+        debug_assert!(j < start + 3);
+        if (j + k) % 2 == 1 {
+            // At this point c_j = c_{j-1} + 1.
+            if xs[j - 1] >= j {
+                let x = xs[j - 1];
+                xs[j - 1] = xs[j - 2];
+                xs[j - 2] = j - 2;
+                *prefix = if xs[j - 1] == j - 1 { j } else { j - 1 };
+                return Some((x, j - 2));
+            }
+        } else {
+            // At this point c_{j-1} = j - 2.
+            if xs[j - 1] + 1 < next(xs, j) {
+                xs[j - 2] = xs[j - 1];
+                xs[j - 1] += 1;
+                *prefix = j - 2;
+                return Some((j - 2, xs[j - 1]));
+            }
+        }
+    }
+    None
+}
+
+/// Iterates over the k-combinations of a set of `n` elements in revolving-door order.
+///
+/// Consecutive k-combinations differ by one element leaving and one element entering. Advancing
+/// takes constant time (the iteration is loopless) and reports the swapped elements:
+///
+/// ```rust
+/// # use number_encoding::combinadics::{RevolvingIter, encode_revolving};
+/// # use number_encoding::combination;
+/// # let n = 5;
+/// # let k = 3;
+/// let mut iter = RevolvingIter::new(n, k);
+/// for i in 0 .. combination(n, k) {
+///     assert_eq!(encode_revolving(iter.get()), i);
+///     let prev = iter.get().to_vec();
+///     match iter.advance() {
+///         Some((out, r#in)) => {
+///             assert!(prev.contains(&out) && !iter.get().contains(&out));
+///             assert!(!prev.contains(&r#in) && iter.get().contains(&r#in));
+///         }
+///         None => assert_eq!(i + 1, combination(n, k)),
+///     }
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::combinadics::RevolvingIter;
+/// let mut iter = RevolvingIter::new(4, 2);
+/// assert_eq!(iter.get(), &[0, 1]);
+/// assert_eq!(iter.advance(), Some((0, 2)));
+/// assert_eq!(iter.get(), &[1, 2]);
+/// assert_eq!(iter.advance(), Some((1, 0)));
+/// assert_eq!(iter.get(), &[0, 2]);
+/// ```
+///
+/// In a no-std environment, you can pass a buffer of size `K`:
+///
+/// ```rust
+/// # use number_encoding::combinadics::RevolvingIter;
+/// # const K: usize = 3;
+/// # let n = 5;
+/// let mut buffer = [0usize; K];
+/// let mut iter = RevolvingIter::new_with_buffer(&mut buffer[..], n);
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct RevolvingIter<T: BorrowMut<[usize]>> {
+    data: T,
+    n: usize,
+    prefix: usize,
+    lent: bool,
+    rank: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl RevolvingIter<Vec<usize>> {
+    /// Constructs an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `k > n`.
+    pub fn new(n: usize, k: usize) -> RevolvingIter<Vec<usize>> {
+        RevolvingIter::new_with_buffer(vec![0; k], n)
+    }
+}
+
+impl<T: BorrowMut<[usize]>> RevolvingIter<T> {
+    /// Constructs an iterator with a buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if the buffer is longer than `n`.
+    pub fn new_with_buffer(mut buffer: T, n: usize) -> RevolvingIter<T> {
+        let xs = buffer.borrow_mut();
+        debug_assert!(xs.len() <= n, "Failed precondition");
+        for (i, x) in xs.iter_mut().enumerate() {
+            *x = i;
+        }
+        let prefix = xs.len();
+        RevolvingIter { data: buffer, n, prefix, lent: false, rank: 0 }
+    }
+
+    /// Returns the current combination.
+    pub fn get(&self) -> &[usize] {
+        self.data.borrow()
+    }
+
+    /// Returns the value of the current combination.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the combination of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        let xs = self.data.borrow_mut();
+        debug_assert!(rank < crate::combination(self.n, xs.len()), "Failed precondition");
+        decode_revolving_mut(rank, xs.len(), xs);
+        self.prefix = xs.iter().enumerate().take_while(|&(i, &x)| i == x).count();
+        self.lent = false;
+        self.rank = rank;
+    }
+
+    /// Advances to the next combination.
+    ///
+    /// Returns the element that left and the element that entered, or `None` if the current
+    /// combination is the last one (in which case it is not modified).
+    pub fn advance(&mut self) -> Option<(usize, usize)> {
+        let swap = step_revolving(self.data.borrow_mut(), self.n, &mut self.prefix)?;
+        self.rank += 1;
+        Some(swap)
+    }
+}
+
+/// Lends the current combination, then advances before each following call.
+impl<T: BorrowMut<[usize]>> Lending for RevolvingIter<T> {
+    type Item = usize;
+
+    fn lend(&mut self) -> Option<&[usize]> {
+        if self.lent {
+            let _ = self.advance()?;
+        }
+        self.lent = true;
+        Some(self.get())
+    }
+}
+
+impl<T: BorrowMut<[usize]>> RankedIter for RevolvingIter<T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
+    }
+}
+
+#[test]
+fn revolving_iter_ok() {
+    for n in 0 .. 10 {
+        for k in 0 ..= n {
+            let mut iter = RevolvingIter::new(n, k);
+            for i in 0 .. crate::combination(n, k) {
+                assert_eq!(iter.rank(), i, "n={n} k={k}");
+                assert_eq!(encode_revolving(iter.get()), i, "n={n} k={k}");
+                assert!(iter.get().iter().all(|&x| x < n), "n={n} k={k}");
+                let prev = iter.get().to_vec();
+                let Some((out, r#in)) = iter.advance() else {
+                    assert_eq!(i + 1, crate::combination(n, k), "n={n} k={k}");
+                    assert_eq!(iter.get(), prev, "n={n} k={k}");
+                    continue;
+                };
+                let mut xs = prev.clone();
+                xs.retain(|&x| x != out);
+                xs.push(r#in);
+                xs.sort();
+                assert_eq!(iter.get(), xs, "n={n} k={k} i={i}");
+                assert!(prev.contains(&out) && !prev.contains(&r#in), "n={n} k={k} i={i}");
+            }
+        }
+    }
+}
+
+#[test]
+fn revolving_iter_seek_ok() {
+    let mut iter = RevolvingIter::new(7, 4);
+    iter.seek(20);
+    assert_eq!(iter.rank(), 20);
+    let r: Vec<_> = iter.vecs().ranked().collect();
+    assert_eq!(r.len(), 15);
+    for (i, xs) in r {
+        assert_eq!(xs, decode_revolving(i, 4), "i={i}");
+    }
+}
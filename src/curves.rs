@@ -0,0 +1,467 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Space-filling curves
+//!
+//! This permits to convert between points of a `d`-dimensional grid (i.e. `[usize]` of length `d`)
+//! and their index along a space-filling curve. Two curves are provided:
+//!
+//! - The [Morton] curve (or Z-order) interleaves the bits of the coordinates: bit `b` of
+//!   coordinate `j` is bit `b * d + j` of the index. It covers the unbounded grid.
+//! - The [Hilbert] curve covers the grid of side `2.pow(bits)` such that consecutive indices are
+//!   neighbor points (i.e. differ by one in exactly one coordinate). It needs the grid size.
+//!
+//! The functions suffixed with `_batch` convert many points at once. Points are stored
+//! contiguously, `d` coordinates each.
+//!
+//! [Morton]: https://en.wikipedia.org/wiki/Z-order_curve
+//! [Hilbert]: https://en.wikipedia.org/wiki/Hilbert_curve
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+const BITS: usize = usize::BITS as usize;
+
+/// Returns the Morton index of a point.
+///
+/// The returned index can be decoded with [`decode_morton`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if the index overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::encode_morton;
+/// assert_eq!(encode_morton(&[0, 0]), 0);
+/// assert_eq!(encode_morton(&[1, 0]), 1);
+/// assert_eq!(encode_morton(&[0, 1]), 2);
+/// assert_eq!(encode_morton(&[1, 1]), 3);
+/// assert_eq!(encode_morton(&[2, 0]), 4);
+/// assert_eq!(encode_morton(&[3, 1, 0]), 0b001011);
+/// ```
+pub fn encode_morton(xs: &[usize]) -> usize {
+    let d = xs.len();
+    let mut z = 0;
+    for (j, &x) in xs.iter().enumerate() {
+        for b in 0 .. BITS - x.leading_zeros() as usize {
+            if x & 1 << b != 0 {
+                let i = b * d + j;
+                debug_assert!(i < BITS, "Failed precondition");
+                z |= 1 << i;
+            }
+        }
+    }
+    z
+}
+
+#[test]
+fn encode_morton_ok() {
+    fn test(xs: &[usize], z: usize) {
+        assert_eq!(encode_morton(xs), z, "xs={xs:?}");
+    }
+    test(&[], 0);
+    test(&[13], 13);
+    test(&[usize::MAX], usize::MAX);
+    test(&[0b101, 0b011], 0b11011);
+    test(&[0b101, 0b011, 0b110], 0b101110011);
+    test(&[usize::MAX >> (BITS / 2), usize::MAX >> (BITS / 2)], usize::MAX);
+}
+
+/// Writes the point of a Morton index to a slice.
+///
+/// The written point can be encoded with [`encode_morton`] to get back `z`.
+///
+/// See [`decode_morton`] for a version that allocates a vector for the point.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.is_empty() && z > 0`.
+pub fn decode_morton_mut(z: usize, xs: &mut [usize]) {
+    let d = xs.len();
+    debug_assert!(d > 0 || z == 0, "Failed precondition");
+    xs.fill(0);
+    for i in 0 .. BITS - z.leading_zeros() as usize {
+        if z & 1 << i != 0 {
+            xs[i % d] |= 1 << (i / d);
+        }
+    }
+}
+
+/// Returns the point of dimension `d` of a Morton index.
+///
+/// The returned point can be encoded with [`encode_morton`] to get back `z`.
+///
+/// See [`decode_morton_mut`] for a version that writes the point to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `d == 0 && z > 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::decode_morton;
+/// assert_eq!(decode_morton(3, 2), &[1, 1]);
+/// assert_eq!(decode_morton(4, 2), &[2, 0]);
+/// assert_eq!(decode_morton(0b001011, 3), &[3, 1, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_morton(z: usize, d: usize) -> Vec<usize> {
+    let mut xs = vec![0; d];
+    decode_morton_mut(z, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_morton_ok() {
+    for d in 1 .. 5 {
+        for z in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+            assert_eq!(encode_morton(&decode_morton(z, d)), z, "z={z} d={d}");
+        }
+    }
+    assert_eq!(decode_morton(0, 0), &[]);
+}
+
+/// Returns the Hilbert index of a point in a grid of side `2.pow(bits)`.
+///
+/// The returned index can be decoded with [`decode_hilbert`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() * bits > usize::BITS` or a coordinate is not less than
+/// `2.pow(bits)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::encode_hilbert;
+/// assert_eq!(encode_hilbert(&[0, 0], 1), 0);
+/// assert_eq!(encode_hilbert(&[0, 1], 1), 1);
+/// assert_eq!(encode_hilbert(&[1, 1], 1), 2);
+/// assert_eq!(encode_hilbert(&[1, 0], 1), 3);
+/// assert_eq!(encode_hilbert(&[2, 0], 2), 14);
+/// ```
+pub fn encode_hilbert(xs: &[usize], bits: usize) -> usize {
+    let d = xs.len();
+    debug_assert!(d * bits <= BITS, "Failed precondition");
+    let fits = |&x: &usize| x.checked_shr(bits as u32).is_none_or(|x| x == 0);
+    debug_assert!(xs.iter().all(fits), "Failed precondition");
+    if d == 0 || bits == 0 {
+        return 0;
+    }
+    let mut buffer = [0; BITS];
+    let ys = &mut buffer[.. d];
+    ys.copy_from_slice(xs);
+    axes_to_transpose(ys, bits);
+    let mut z = 0;
+    for b in (0 .. bits).rev() {
+        for &y in ys.iter() {
+            z = z << 1 | (y >> b & 1);
+        }
+    }
+    z
+}
+
+#[test]
+fn encode_hilbert_ok() {
+    fn test(xs: &[usize], bits: usize, z: usize) {
+        assert_eq!(encode_hilbert(xs, bits), z, "xs={xs:?} bits={bits}");
+    }
+    test(&[], 3, 0);
+    test(&[0, 0], 0, 0);
+    test(&[5], 3, 5);
+    test(&[0, 0], 2, 0);
+    test(&[1, 0], 2, 1);
+    test(&[1, 1], 2, 2);
+    test(&[0, 1], 2, 3);
+    test(&[0, 2], 2, 4);
+    test(&[0, 3], 2, 5);
+    test(&[1, 3], 2, 6);
+    test(&[1, 2], 2, 7);
+    test(&[2, 2], 2, 8);
+    test(&[2, 3], 2, 9);
+    test(&[3, 3], 2, 10);
+    test(&[3, 2], 2, 11);
+    test(&[3, 1], 2, 12);
+    test(&[2, 1], 2, 13);
+    test(&[2, 0], 2, 14);
+    test(&[3, 0], 2, 15);
+    test(&[usize::MAX], BITS, usize::MAX);
+}
+
+/// Writes the point of a Hilbert index in a grid of side `2.pow(bits)` to a slice.
+///
+/// The written point can be encoded with [`encode_hilbert`] to get back `z`.
+///
+/// See [`decode_hilbert`] for a version that allocates a vector for the point.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() * bits > usize::BITS` or `z` is not less than
+/// `2.pow(xs.len() * bits)`.
+pub fn decode_hilbert_mut(z: usize, bits: usize, xs: &mut [usize]) {
+    let d = xs.len();
+    debug_assert!(d * bits <= BITS, "Failed precondition");
+    debug_assert!(z.checked_shr((d * bits) as u32).is_none_or(|x| x == 0), "Failed precondition");
+    xs.fill(0);
+    if d == 0 || bits == 0 {
+        return;
+    }
+    for b in 0 .. bits {
+        for (j, x) in xs.iter_mut().enumerate() {
+            *x |= (z >> (b * d + d - 1 - j) & 1) << b;
+        }
+    }
+    transpose_to_axes(xs, bits);
+}
+
+/// Returns the point of dimension `d` of a Hilbert index in a grid of side `2.pow(bits)`.
+///
+/// The returned point can be encoded with [`encode_hilbert`] to get back `z`.
+///
+/// See [`decode_hilbert_mut`] for a version that writes the point to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `d * bits > usize::BITS` or `z` is not less than
+/// `2.pow(d * bits)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::decode_hilbert;
+/// assert_eq!(decode_hilbert(2, 1, 2), &[1, 1]);
+/// assert_eq!(decode_hilbert(14, 2, 2), &[2, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_hilbert(z: usize, bits: usize, d: usize) -> Vec<usize> {
+    let mut xs = vec![0; d];
+    decode_hilbert_mut(z, bits, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_hilbert_ok() {
+    for d in 1 .. 5 {
+        for bits in 0 ..= 12 / d {
+            let mut previous = decode_hilbert(0, bits, d);
+            assert_eq!(previous, vec![0; d]);
+            for z in 0 .. 1 << (d * bits) {
+                let xs = decode_hilbert(z, bits, d);
+                assert_eq!(encode_hilbert(&xs, bits), z, "z={z} bits={bits} d={d}");
+                let distance: usize = xs.iter().zip(&previous).map(|(&x, &y)| x.abs_diff(y)).sum();
+                assert_eq!(distance, (z > 0) as usize, "z={z} bits={bits} d={d}");
+                previous = xs;
+            }
+        }
+    }
+    for d in [1, 2, 4, 8] {
+        let bits = BITS / d;
+        for z in usize::MAX - 1000 ..= usize::MAX {
+            let xs = decode_hilbert(z, bits, d);
+            assert_eq!(encode_hilbert(&xs, bits), z, "z={z} bits={bits} d={d}");
+        }
+    }
+}
+
+// The following two functions are the algorithm of John Skilling in "Programming the Hilbert
+// curve" (AIP Conference Proceedings 707, 2004). The transposed index stores bit
+// `b * d + d - 1 - j` of the index as bit `b` of coordinate `j`.
+
+/// Converts a point to its transposed Hilbert index in place.
+fn axes_to_transpose(xs: &mut [usize], bits: usize) {
+    let d = xs.len();
+    // Inverse undo.
+    for b in (1 .. bits).rev() {
+        let q = 1 << b;
+        let p = q - 1;
+        for i in 0 .. d {
+            exchange(xs, i, q, p);
+        }
+    }
+    // Gray encode.
+    for i in 1 .. d {
+        xs[i] ^= xs[i - 1];
+    }
+    let mut t = 0;
+    for b in (1 .. bits).rev() {
+        let q = 1 << b;
+        if xs[d - 1] & q != 0 {
+            t ^= q - 1;
+        }
+    }
+    for x in xs {
+        *x ^= t;
+    }
+}
+
+/// Converts a transposed Hilbert index to its point in place.
+fn transpose_to_axes(xs: &mut [usize], bits: usize) {
+    let d = xs.len();
+    // Gray decode.
+    let t = xs[d - 1] >> 1;
+    for i in (1 .. d).rev() {
+        xs[i] ^= xs[i - 1];
+    }
+    xs[0] ^= t;
+    // Undo excess work.
+    for b in 1 .. bits {
+        let q = 1 << b;
+        let p = q - 1;
+        for i in (0 .. d).rev() {
+            exchange(xs, i, q, p);
+        }
+    }
+}
+
+/// Inverts the low bits of the first coordinate if bit `q` of coordinate `i` is set, and exchanges
+/// the low bits of the first coordinate and coordinate `i` otherwise.
+fn exchange(xs: &mut [usize], i: usize, q: usize, p: usize) {
+    if xs[i] & q != 0 {
+        xs[0] ^= p;
+    } else {
+        let t = (xs[0] ^ xs[i]) & p;
+        xs[0] ^= t;
+        xs[i] ^= t;
+    }
+}
+
+/// Writes the Morton indices of points to a slice.
+///
+/// The points are stored contiguously in `points`, `d` coordinates each.
+///
+/// # Panics
+///
+/// Panics in debug mode if `d == 0`, `points.len() != d * zs.len()`, or an index overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::encode_morton_batch;
+/// let mut zs = [0; 3];
+/// encode_morton_batch(&[0, 0, 1, 0, 1, 1], 2, &mut zs);
+/// assert_eq!(zs, [0, 1, 3]);
+/// ```
+pub fn encode_morton_batch(points: &[usize], d: usize, zs: &mut [usize]) {
+    debug_assert!(d > 0 && points.len() == d * zs.len(), "Failed precondition");
+    for (xs, z) in points.chunks_exact(d).zip(zs) {
+        *z = encode_morton(xs);
+    }
+}
+
+/// Writes the points of Morton indices to a slice.
+///
+/// The points are stored contiguously in `points`, `d` coordinates each.
+///
+/// # Panics
+///
+/// Panics in debug mode if `d == 0` or `points.len() != d * zs.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::decode_morton_batch;
+/// let mut points = [0; 6];
+/// decode_morton_batch(&[0, 1, 3], 2, &mut points);
+/// assert_eq!(points, [0, 0, 1, 0, 1, 1]);
+/// ```
+pub fn decode_morton_batch(zs: &[usize], d: usize, points: &mut [usize]) {
+    debug_assert!(d > 0 && points.len() == d * zs.len(), "Failed precondition");
+    for (&z, xs) in zs.iter().zip(points.chunks_exact_mut(d)) {
+        decode_morton_mut(z, xs);
+    }
+}
+
+#[test]
+fn morton_batch_ok() {
+    let zs: Vec<usize> = (0 .. 100).map(|i| i * i).collect();
+    for d in 1 .. 4 {
+        let mut points = vec![0; d * zs.len()];
+        decode_morton_batch(&zs, d, &mut points);
+        for (&z, xs) in zs.iter().zip(points.chunks(d)) {
+            assert_eq!(decode_morton(z, d), xs);
+        }
+        let mut ys = vec![0; zs.len()];
+        encode_morton_batch(&points, d, &mut ys);
+        assert_eq!(ys, zs);
+    }
+}
+
+/// Writes the Hilbert indices of points in a grid of side `2.pow(bits)` to a slice.
+///
+/// The points are stored contiguously in `points`, `d` coordinates each.
+///
+/// # Panics
+///
+/// Panics in debug mode if `d == 0`, `points.len() != d * zs.len()`, or the preconditions of
+/// [`encode_hilbert`] do not hold.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::encode_hilbert_batch;
+/// let mut zs = [0; 3];
+/// encode_hilbert_batch(&[0, 0, 1, 1, 1, 0], 2, 1, &mut zs);
+/// assert_eq!(zs, [0, 2, 3]);
+/// ```
+pub fn encode_hilbert_batch(points: &[usize], d: usize, bits: usize, zs: &mut [usize]) {
+    debug_assert!(d > 0 && points.len() == d * zs.len(), "Failed precondition");
+    for (xs, z) in points.chunks_exact(d).zip(zs) {
+        *z = encode_hilbert(xs, bits);
+    }
+}
+
+/// Writes the points of Hilbert indices in a grid of side `2.pow(bits)` to a slice.
+///
+/// The points are stored contiguously in `points`, `d` coordinates each.
+///
+/// # Panics
+///
+/// Panics in debug mode if `d == 0`, `points.len() != d * zs.len()`, or the preconditions of
+/// [`decode_hilbert_mut`] do not hold.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::curves::decode_hilbert_batch;
+/// let mut points = [0; 6];
+/// decode_hilbert_batch(&[0, 2, 3], 2, 1, &mut points);
+/// assert_eq!(points, [0, 0, 1, 1, 1, 0]);
+/// ```
+pub fn decode_hilbert_batch(zs: &[usize], d: usize, bits: usize, points: &mut [usize]) {
+    debug_assert!(d > 0 && points.len() == d * zs.len(), "Failed precondition");
+    for (&z, xs) in zs.iter().zip(points.chunks_exact_mut(d)) {
+        decode_hilbert_mut(z, bits, xs);
+    }
+}
+
+#[test]
+fn hilbert_batch_ok() {
+    let zs: Vec<usize> = (0 .. 64).map(|i| i * i).collect();
+    for d in 1 .. 4 {
+        let bits = 12 / d;
+        let mut points = vec![0; d * zs.len()];
+        decode_hilbert_batch(&zs, d, bits, &mut points);
+        for (&z, xs) in zs.iter().zip(points.chunks(d)) {
+            assert_eq!(decode_hilbert(z, bits, d), xs);
+        }
+        let mut ys = vec![0; zs.len()];
+        encode_hilbert_batch(&points, d, bits, &mut ys);
+        assert_eq!(ys, zs);
+    }
+}
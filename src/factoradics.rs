@@ -16,10 +16,17 @@
 //!
 //! See [wikipedia] for more information.
 //!
+//! This module also provides the Steinhaus-Johnson-Trotter order (see [`encode_sjt`]) in which
+//! consecutive permutations differ by one adjacent transposition, and Heap's order (see
+//! [`HeapIter`]) in which they differ by one swap.
+//!
 //! [wikipedia]: https://en.wikipedia.org/wiki/Factorial_number_system
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::iter::{Lending, LendingBack, RankedIter};
 
 /// Applies the permutation of the value `p` to the slice `xs`.
 ///
@@ -105,6 +112,71 @@ fn decode_ok() {
     test(3, 5, &[2, 1, 0]);
 }
 
+/// Writes the permutations of the values in `range` to `out` with `layout`.
+///
+/// This is faster than calling [`decode_mut`] for each value: only the first value is decoded, the
+/// next permutations are obtained by stepping.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing, `range` is out of range, or
+/// `out.len() != xs.len() * range.len()`. Without the `alloc` feature, panics if `layout` is
+/// [`ColumnMajor`](crate::Layout::ColumnMajor) and `xs.len() > 64`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::decode_range_into;
+/// # use number_encoding::Layout;
+/// let mut out = [0; 9];
+/// decode_range_into(&[0, 1, 2], 1 .. 4, Layout::RowMajor, &mut out);
+/// assert_eq!(out, [0, 2, 1, 1, 0, 2, 1, 2, 0]);
+/// decode_range_into(&[0, 1, 2], 1 .. 4, Layout::ColumnMajor, &mut out);
+/// assert_eq!(out, [0, 1, 1, 2, 0, 2, 1, 2, 0]);
+/// ```
+pub fn decode_range_into<T: Clone + Ord>(
+    xs: &[T],
+    range: Range<usize>,
+    layout: crate::Layout,
+    out: &mut [T],
+) {
+    debug_assert!(range.end <= crate::factorial(xs.len()), "Failed precondition");
+    debug_assert_eq!(out.len(), xs.len() * range.len(), "Failed precondition");
+    let init = |ys: &mut [T]| {
+        ys.clone_from_slice(xs);
+        decode_mut(ys, range.start);
+    };
+    crate::write_range(xs.len(), range.len(), layout, out, init, |xs| {
+        let _ = next_permutation(xs);
+    });
+}
+
+#[test]
+fn decode_range_into_ok() {
+    for layout in [crate::Layout::RowMajor, crate::Layout::ColumnMajor] {
+        for xs in [&[][..], &[0], &[0, 1], &[0, 1, 2], &[0, 1, 2, 3]] {
+            let count = crate::factorial(xs.len());
+            let n = xs.len();
+            for start in 0 ..= count {
+                for end in start ..= count {
+                    let len = end - start;
+                    let mut out = xs.repeat(len);
+                    decode_range_into(xs, start .. end, layout, &mut out);
+                    for i in 0 .. len {
+                        let ys: Vec<_> = match layout {
+                            crate::Layout::RowMajor => out[i * n .. (i + 1) * n].to_vec(),
+                            crate::Layout::ColumnMajor => {
+                                (0 .. n).map(|j| out[j * len + i]).collect()
+                            }
+                        };
+                        assert_eq!(ys, decode(xs, start + i), "xs={xs:?} start={start} i={i}");
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Returns the value of a permutation.
 ///
 /// The returned value can be decoded with [`decode`] to get back `xs`.
@@ -160,6 +232,104 @@ fn encode_ok() {
     test(&[2, 1, 0], 5);
 }
 
+/// Rearranges a slice to the next permutation in value order.
+///
+/// Returns `false` if the slice is the last permutation (i.e. non-increasing), in which case it is
+/// rearranged to the first permutation (i.e. non-decreasing). This also works for multiset
+/// permutations (see [`multinadics`](crate::multinadics)).
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::next_permutation;
+/// let mut xs = [0, 2, 1];
+/// assert!(next_permutation(&mut xs));
+/// assert_eq!(xs, [1, 0, 2]);
+/// let mut xs = [2, 1, 0];
+/// assert!(!next_permutation(&mut xs));
+/// assert_eq!(xs, [0, 1, 2]);
+/// ```
+pub fn next_permutation<T: Ord>(xs: &mut [T]) -> bool {
+    let n = xs.len();
+    if n == 0 {
+        return false;
+    }
+    let mut i = n - 1;
+    while i > 0 && xs[i - 1] >= xs[i] {
+        i -= 1;
+    }
+    if i == 0 {
+        xs.reverse();
+        return false;
+    }
+    xs[i ..].reverse();
+    let j = xs[i ..].iter().position(|x| x > &xs[i - 1]).unwrap();
+    xs.swap(i - 1, i + j);
+    true
+}
+
+#[test]
+fn next_permutation_ok() {
+    for n in 0 .. 6 {
+        let mut xs: Vec<usize> = (0 .. n).collect();
+        for p in 1 .. crate::factorial(n) {
+            assert!(next_permutation(&mut xs), "n={n} p={p}");
+            assert_eq!(encode(&xs), p, "n={n} p={p}");
+        }
+        assert!(!next_permutation(&mut xs), "n={n}");
+        assert_eq!(encode(&xs), 0, "n={n}");
+    }
+}
+
+/// Rearranges a slice to the previous permutation in value order.
+///
+/// Returns `false` if the slice is the first permutation (i.e. non-decreasing), in which case it is
+/// rearranged to the last permutation (i.e. non-increasing). This also works for multiset
+/// permutations (see [`multinadics`](crate::multinadics)).
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::prev_permutation;
+/// let mut xs = [1, 0, 2];
+/// assert!(prev_permutation(&mut xs));
+/// assert_eq!(xs, [0, 2, 1]);
+/// let mut xs = [0, 1, 2];
+/// assert!(!prev_permutation(&mut xs));
+/// assert_eq!(xs, [2, 1, 0]);
+/// ```
+pub fn prev_permutation<T: Ord>(xs: &mut [T]) -> bool {
+    let n = xs.len();
+    if n == 0 {
+        return false;
+    }
+    let mut i = n - 1;
+    while i > 0 && xs[i - 1] <= xs[i] {
+        i -= 1;
+    }
+    if i == 0 {
+        xs.reverse();
+        return false;
+    }
+    xs[i ..].reverse();
+    let j = xs[i ..].iter().position(|x| x < &xs[i - 1]).unwrap();
+    xs.swap(i - 1, i + j);
+    true
+}
+
+#[test]
+fn prev_permutation_ok() {
+    for n in 0 .. 6 {
+        let mut xs: Vec<usize> = (0 .. n).rev().collect();
+        for p in (0 .. crate::factorial(n) - 1).rev() {
+            assert!(prev_permutation(&mut xs), "n={n} p={p}");
+            assert_eq!(encode(&xs), p, "n={n} p={p}");
+        }
+        assert!(!prev_permutation(&mut xs), "n={n}");
+        assert_eq!(encode(&xs), crate::factorial(n) - 1, "n={n}");
+    }
+}
+
 /// Iterates over all permutations of a slice.
 ///
 /// The permutations are iterated in value order:
@@ -198,9 +368,13 @@ fn encode_ok() {
 ///     process(xs);
 /// }
 /// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
 pub struct Iter<'a, T> {
     data: &'a mut [T],
     state: IterState,
+    rank: usize,
 }
 
 enum IterState {
@@ -217,7 +391,63 @@ impl<'a, T: Ord> Iter<'a, T> {
     /// Panics in debug mode if `xs` is not increasing.
     pub fn new(xs: &mut [T]) -> Iter<T> {
         debug_assert!(crate::is_ordered_set(xs));
-        Iter { data: xs, state: IterState::New }
+        Iter { data: xs, state: IterState::New, rank: 0 }
+    }
+
+    /// Constructs an iterator starting from a given permutation.
+    ///
+    /// The iteration ends after the last permutation, at which point the slice is increasing.
+    pub fn new_from(xs: &mut [T]) -> Iter<'_, T> {
+        let rank = encode(xs);
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Constructs an iterator starting from the permutation of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not increasing or `rank` is out of range.
+    pub fn new_at_rank(xs: &mut [T], rank: usize) -> Iter<'_, T> {
+        decode_mut(xs, rank);
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Constructs an iterator starting from the last permutation with an increasing slice.
+    ///
+    /// This is meant for iteration with [`prev`](Iter::prev).
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not increasing or the number of permutations overflows
+    /// (i.e. `xs.len() > 20` on 64-bit platforms).
+    pub fn new_rev(xs: &mut [T]) -> Iter<'_, T> {
+        debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+        let rank = crate::factorial(xs.len()) - 1;
+        xs.reverse();
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Returns the value of the current permutation.
+    ///
+    /// The current permutation is the one last returned by [`next`](Iter::next), or the one it will
+    /// return if it was not called yet. After the end of the iteration, this is the number of
+    /// permutations.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the permutation of a given value.
+    ///
+    /// The next call to [`next`](Iter::next) returns this permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        self.data.sort_unstable();
+        decode_mut(self.data, rank);
+        self.state = IterState::New;
+        self.rank = rank;
     }
 
     /// Returns the next permutation.
@@ -228,7 +458,8 @@ impl<'a, T: Ord> Iter<'a, T> {
         match self.state {
             IterState::New => self.state = IterState::Running,
             IterState::Running => {
-                if self.advance() {
+                self.rank += 1;
+                if !next_permutation(self.data) {
                     self.state = IterState::Done;
                 }
             }
@@ -241,23 +472,53 @@ impl<'a, T: Ord> Iter<'a, T> {
         }
     }
 
-    fn advance(&mut self) -> bool {
-        let k = self.data.len();
-        if k == 0 {
-            return true;
-        }
-        let mut i = k - 1;
-        while i > 0 && self.data[i - 1] > self.data[i] {
-            i -= 1;
+    /// Returns the previous permutation.
+    ///
+    /// This is the reverse of [`next`](Iter::next): the first call returns the current permutation
+    /// and the iteration ends after the first permutation. If iteration is over, returns `None` and
+    /// the slice is restored to its first value.
+    pub fn prev(&mut self) -> Option<&[T]> {
+        match self.state {
+            IterState::New => self.state = IterState::Running,
+            IterState::Running => {
+                if prev_permutation(self.data) {
+                    self.rank -= 1;
+                } else {
+                    self.data.reverse();
+                    self.state = IterState::Done;
+                }
+            }
+            IterState::Done => (),
         }
-        if i == 0 {
-            self.data.reverse();
-            return true;
+        match self.state {
+            IterState::New => unreachable!(),
+            IterState::Running => Some(self.data),
+            IterState::Done => None,
         }
-        self.data[i ..].reverse();
-        let j = self.data[i ..].iter().position(|x| x > &self.data[i - 1]).unwrap();
-        self.data.swap(i - 1, i + j);
-        false
+    }
+}
+
+impl<T: Ord> Lending for Iter<'_, T> {
+    type Item = T;
+
+    fn lend(&mut self) -> Option<&[T]> {
+        self.next()
+    }
+}
+
+impl<T: Ord> LendingBack for Iter<'_, T> {
+    fn lend_back(&mut self) -> Option<&[T]> {
+        self.prev()
+    }
+}
+
+impl<T: Ord> RankedIter for Iter<'_, T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
     }
 }
 
@@ -306,3 +567,439 @@ fn iter_ok() {
         &[3, 2, 1, 0],
     ]);
 }
+
+#[test]
+fn iter_rank_ok() {
+    let mut xs = [0, 1, 2, 3];
+    let mut iter = Iter::new_at_rank(&mut xs, 5);
+    for i in 5 .. 24 {
+        assert_eq!(encode(iter.next().unwrap()), i);
+        assert_eq!(iter.rank(), i);
+    }
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.rank(), 24);
+    iter.seek(16);
+    assert_eq!(iter.next(), Some(&[2, 3, 0, 1][..]));
+    assert_eq!(iter.rank(), 16);
+    let mut xs = [3, 1, 2, 0];
+    let mut iter = Iter::new_from(&mut xs);
+    assert_eq!(iter.rank(), 21);
+    assert_eq!(iter.next(), Some(&[3, 1, 2, 0][..]));
+    assert_eq!(iter.next(), Some(&[3, 2, 0, 1][..]));
+    assert_eq!(iter.rank(), 22);
+}
+
+/// Applies the permutation of a value in Steinhaus-Johnson-Trotter order to a slice.
+///
+/// The written permutation can be encoded with [`encode_sjt`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::factoradics::{decode_sjt_mut, encode_sjt};
+/// # let mut xs = [0, 1, 2, 3];
+/// # let p = 15;
+/// decode_sjt_mut(&mut xs, p);
+/// assert_eq!(encode_sjt(&xs), p);
+/// ```
+///
+/// See [`decode_sjt`] for a version that allocates a vector for the permutation.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or `p` is out of range.
+pub fn decode_sjt_mut<T: Ord>(xs: &mut [T], p: usize) {
+    let n = xs.len();
+    let mut m = crate::factorial(n);
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    debug_assert!(p < m, "Failed precondition");
+    // The i-th smallest element is inserted in the permutation of the smaller ones. It sweeps from
+    // right to left when that permutation has an even value, and from left to right otherwise.
+    for i in 1 ..= n {
+        m /= i;
+        let q = p / m;
+        let j = if (q / i).is_multiple_of(2) { i - 1 - q % i } else { q % i };
+        xs[j .. i].rotate_right(1);
+    }
+}
+
+/// Returns the permutation of the value `p` in Steinhaus-Johnson-Trotter order to the slice `xs`.
+///
+/// The returned permutation can be encoded with [`encode_sjt`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::factoradics::{decode_sjt, encode_sjt};
+/// # let xs = [0, 1, 2, 3];
+/// # let p = 15;
+/// let xs = decode_sjt(&xs, p);
+/// assert_eq!(encode_sjt(&xs), p);
+/// ```
+///
+/// See [`decode_sjt_mut`] for a version that applies the permutation to the slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or `p` is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::decode_sjt;
+/// assert_eq!(decode_sjt(&[0, 1, 2], 0), &[0, 1, 2]);
+/// assert_eq!(decode_sjt(&[0, 1, 2], 1), &[0, 2, 1]);
+/// assert_eq!(decode_sjt(&[0, 1, 2], 2), &[2, 0, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_sjt<T: Clone + Ord>(xs: &[T], p: usize) -> Vec<T> {
+    let mut xs = xs.to_vec();
+    decode_sjt_mut(&mut xs[..], p);
+    xs
+}
+
+#[test]
+fn decode_sjt_ok() {
+    fn test(d: usize, p: usize, e: &[usize]) {
+        let mut r: Vec<_> = (0 .. d).collect();
+        decode_sjt_mut(&mut r, p);
+        assert_eq!(r, e, "p={p}");
+    }
+    test(0, 0, &[]);
+    test(1, 0, &[0]);
+    test(2, 0, &[0, 1]);
+    test(2, 1, &[1, 0]);
+    test(3, 0, &[0, 1, 2]);
+    test(3, 1, &[0, 2, 1]);
+    test(3, 2, &[2, 0, 1]);
+    test(3, 3, &[2, 1, 0]);
+    test(3, 4, &[1, 2, 0]);
+    test(3, 5, &[1, 0, 2]);
+    test(4, 6, &[0, 2, 3, 1]);
+    test(4, 23, &[1, 0, 2, 3]);
+}
+
+/// Returns the value of a permutation in Steinhaus-Johnson-Trotter order.
+///
+/// The returned value can be decoded with [`decode_sjt`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::factoradics::{decode_sjt, encode_sjt};
+/// # let xs = &[2, 0, 3, 1];
+/// let mut ys = xs.to_vec();
+/// ys.sort();
+/// let p = encode_sjt(xs);
+/// assert_eq!(decode_sjt(&ys, p), xs);
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::encode_sjt;
+/// assert_eq!(encode_sjt(&[0, 1, 2]), 0);
+/// assert_eq!(encode_sjt(&[0, 2, 1]), 1);
+/// assert_eq!(encode_sjt(&[2, 0, 1]), 2);
+/// ```
+pub fn encode_sjt<T: Ord>(xs: &[T]) -> usize {
+    let mut r = 0;
+    let mut prev: Option<&T> = None;
+    for m in 1 ..= xs.len() {
+        let j =
+            (0 .. xs.len()).filter(|&j| prev.is_none_or(|x| &xs[j] > x)).min_by_key(|&j| &xs[j]);
+        let j = j.unwrap();
+        let i = xs[.. j].iter().filter(|&x| x < &xs[j]).count();
+        r = m * r + if r.is_multiple_of(2) { m - 1 - i } else { i };
+        prev = Some(&xs[j]);
+    }
+    r
+}
+
+#[test]
+fn encode_sjt_ok() {
+    for n in 0 .. 6 {
+        let xs: Vec<usize> = (0 .. n).collect();
+        for p in 0 .. crate::factorial(n) {
+            assert_eq!(encode_sjt(&decode_sjt(&xs, p)), p, "n={n} p={p}");
+        }
+    }
+}
+
+/// Iterates over all permutations of a slice in Steinhaus-Johnson-Trotter order.
+///
+/// Consecutive permutations differ by one adjacent transposition. Advancing takes constant
+/// amortized time and reports the swapped indices:
+///
+/// ```rust
+/// # use number_encoding::factoradics::{SjtIter, encode_sjt};
+/// # let mut xs = [0, 1, 2, 3];
+/// let mut iter = SjtIter::new(&mut xs);
+/// let mut i = 0;
+/// loop {
+///     assert_eq!(encode_sjt(iter.get()), i);
+///     i += 1;
+///     match iter.advance() {
+///         Some((a, b)) => assert_eq!(a + 1, b),
+///         None => break,
+///     }
+/// }
+/// assert_eq!(i, 24);
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::SjtIter;
+/// let mut xs = [0, 1, 2];
+/// let mut iter = SjtIter::new(&mut xs);
+/// assert_eq!(iter.advance(), Some((1, 2)));
+/// assert_eq!(iter.get(), &[0, 2, 1]);
+/// assert_eq!(iter.advance(), Some((0, 1)));
+/// assert_eq!(iter.get(), &[2, 0, 1]);
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct SjtIter<'a, T> {
+    data: &'a mut [T],
+    lent: bool,
+    rank: usize,
+}
+
+impl<'a, T: Ord> SjtIter<'a, T> {
+    /// Constructs an iterator with an increasing slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not increasing.
+    pub fn new(xs: &mut [T]) -> SjtIter<'_, T> {
+        debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+        SjtIter { data: xs, lent: false, rank: 0 }
+    }
+
+    /// Constructs an iterator starting from the permutation of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not increasing or `rank` is out of range.
+    pub fn new_at_rank(xs: &mut [T], rank: usize) -> SjtIter<'_, T> {
+        decode_sjt_mut(xs, rank);
+        SjtIter { data: xs, lent: false, rank }
+    }
+
+    /// Returns the current permutation.
+    pub fn get(&self) -> &[T] {
+        self.data
+    }
+
+    /// Returns the value of the current permutation.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the permutation of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        self.data.sort_unstable();
+        decode_sjt_mut(self.data, rank);
+        self.lent = false;
+        self.rank = rank;
+    }
+
+    /// Advances to the next permutation.
+    ///
+    /// Returns the swapped indices `(i, i + 1)`, or `None` if the current permutation is the last
+    /// one (in which case it is not modified).
+    pub fn advance(&mut self) -> Option<(usize, usize)> {
+        // The m-th smallest element moves unless it is at the end of its sweep, in which case the
+        // permutation of the smaller ones advances. The elements at the end of their sweep to the
+        // left shift the indices.
+        let mut offset = 0;
+        let mut q = self.rank;
+        for m in (2 ..= self.data.len()).rev() {
+            let (r, i) = (q / m, q % m);
+            if i < m - 1 {
+                let j = offset + if r.is_multiple_of(2) { m - 2 - i } else { i };
+                self.data.swap(j, j + 1);
+                self.rank += 1;
+                return Some((j, j + 1));
+            }
+            offset += r.is_multiple_of(2) as usize;
+            q = r;
+        }
+        None
+    }
+}
+
+/// Lends the current permutation, then advances before each following call.
+impl<'a, T: Ord> Lending for SjtIter<'a, T> {
+    type Item = T;
+
+    fn lend(&mut self) -> Option<&[T]> {
+        if self.lent {
+            let _ = self.advance()?;
+        }
+        self.lent = true;
+        Some(self.get())
+    }
+}
+
+impl<'a, T: Ord> RankedIter for SjtIter<'a, T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
+    }
+}
+
+#[test]
+fn sjt_iter_ok() {
+    for n in 0 .. 7 {
+        let mut xs: Vec<usize> = (0 .. n).collect();
+        let mut iter = SjtIter::new(&mut xs);
+        for p in 0 .. crate::factorial(n) {
+            assert_eq!(iter.rank(), p, "n={n}");
+            assert_eq!(encode_sjt(iter.get()), p, "n={n}");
+            let prev = iter.get().to_vec();
+            let Some((a, b)) = iter.advance() else {
+                assert_eq!(p + 1, crate::factorial(n), "n={n}");
+                assert_eq!(iter.get(), prev, "n={n}");
+                continue;
+            };
+            let mut ys = prev;
+            ys.swap(a, b);
+            assert_eq!((a + 1, iter.get()), (b, &ys[..]), "n={n} p={p}");
+        }
+    }
+    let mut xs = [0, 1, 2, 3];
+    let mut iter = SjtIter::new_at_rank(&mut xs, 20);
+    assert_eq!(iter.get(), &[3, 1, 0, 2]);
+    iter.seek(6);
+    let r: Vec<_> = iter.vecs().ranked().collect();
+    assert_eq!(r.len(), 18);
+    for (p, xs) in r {
+        assert_eq!(xs, decode_sjt(&[0, 1, 2, 3], p), "p={p}");
+    }
+}
+
+/// Iterates over all permutations of a slice in Heap's order.
+///
+/// Consecutive permutations differ by one swap. Advancing takes constant amortized time and
+/// reports the swapped indices:
+///
+/// ```rust
+/// # use number_encoding::factoradics::HeapIter;
+/// # let mut xs = [0, 1, 2, 3];
+/// let mut iter = HeapIter::new(&mut xs);
+/// let mut prev = iter.get().to_vec();
+/// while let Some((a, b)) = iter.advance() {
+///     prev.swap(a, b);
+///     assert_eq!(iter.get(), prev);
+/// }
+/// assert_eq!(iter.rank(), 23);
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::factoradics::HeapIter;
+/// let mut xs = [0, 1, 2];
+/// let mut iter = HeapIter::new(&mut xs);
+/// assert_eq!(iter.advance(), Some((0, 1)));
+/// assert_eq!(iter.get(), &[1, 0, 2]);
+/// assert_eq!(iter.advance(), Some((0, 2)));
+/// assert_eq!(iter.get(), &[2, 0, 1]);
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct HeapIter<'a, T> {
+    data: &'a mut [T],
+    lent: bool,
+    rank: usize,
+}
+
+impl<'a, T> HeapIter<'a, T> {
+    /// Constructs an iterator with a slice.
+    ///
+    /// The slice does not need to be ordered. It is the first permutation of the iteration.
+    pub fn new(xs: &mut [T]) -> HeapIter<'_, T> {
+        HeapIter { data: xs, lent: false, rank: 0 }
+    }
+
+    /// Returns the current permutation.
+    pub fn get(&self) -> &[T] {
+        self.data
+    }
+
+    /// Returns the number of permutations before the current one.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Advances to the next permutation.
+    ///
+    /// Returns the swapped indices `(i, j)` with `i < j`, or `None` if the current permutation is
+    /// the last one (in which case it is not modified).
+    pub fn advance(&mut self) -> Option<(usize, usize)> {
+        // The rank is the counter of the iterative algorithm in the factorial number system: the
+        // lowest digit that is not maximal is incremented.
+        let mut f = 1;
+        for i in 1 .. self.data.len() {
+            f *= i;
+            let c = self.rank / f % (i + 1);
+            if c < i {
+                let j = if i.is_multiple_of(2) { 0 } else { c };
+                self.data.swap(j, i);
+                self.rank += 1;
+                return Some((j, i));
+            }
+        }
+        None
+    }
+}
+
+/// Lends the current permutation, then advances before each following call.
+///
+/// This type does not implement [`RankedIter`], since Heap's order has no decoding. The rank of
+/// the last lent permutation is given by [`HeapIter::rank`].
+impl<'a, T> Lending for HeapIter<'a, T> {
+    type Item = T;
+
+    fn lend(&mut self) -> Option<&[T]> {
+        if self.lent {
+            let _ = self.advance()?;
+        }
+        self.lent = true;
+        Some(self.get())
+    }
+}
+
+#[test]
+fn heap_iter_ok() {
+    for n in 0 .. 7 {
+        let mut xs: Vec<usize> = (0 .. n).collect();
+        let mut iter = HeapIter::new(&mut xs);
+        let mut seen = std::collections::HashSet::new();
+        for p in 0 .. crate::factorial(n) {
+            assert_eq!(iter.rank(), p, "n={n}");
+            assert!(seen.insert(iter.get().to_vec()), "n={n} p={p}");
+            let prev = iter.get().to_vec();
+            let Some((a, b)) = iter.advance() else {
+                assert_eq!(p + 1, crate::factorial(n), "n={n}");
+                assert_eq!(iter.get(), prev, "n={n}");
+                continue;
+            };
+            let mut ys = prev;
+            ys.swap(a, b);
+            assert_eq!((a < b, iter.get()), (true, &ys[..]), "n={n} p={p}");
+        }
+    }
+    let mut xs = [0, 1, 2];
+    let mut iter = HeapIter::new(&mut xs);
+    for p in 0 .. 6 {
+        assert!(iter.lend().is_some());
+        assert_eq!(iter.rank(), p);
+    }
+    assert_eq!(iter.lend(), None);
+}
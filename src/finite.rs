@@ -0,0 +1,566 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finite collection number system
+//!
+//! This permits to convert between values and finite collections of values of any size:
+//!
+//! - Sets are increasing slices (e.g. `[1, 4, 5]`). A set is the value with the bits of its
+//!   elements set.
+//! - Multisets are non-decreasing slices (e.g. `[1, 1, 3]`). A multiset is the set obtained by
+//!   adding its position to each element (e.g. `[1, 2, 5]`).
+//! - Lists are any slices (e.g. `[1, 0, 2]`). A list is the set of its partial sums plus position
+//!   (e.g. `[1, 2, 5]`).
+//!
+//! The functions suffixed with `_graded` use another order. Sets are ordered by maximum first,
+//! then by length, then in the [combinatorial number system](crate::combinadics) (i.e. colex
+//! order). Multisets are thus ordered by maximum plus length first, and lists by sum plus length
+//! first. In both orders, the sets with elements less than `n` are the first `2.pow(n)` values.
+//!
+//! The encoding functions return `None` if the value overflows. The decoding functions never
+//! overflow.
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+const BITS: usize = usize::BITS as usize;
+
+/// Returns the binomial coefficient of `n < BITS`, which does not overflow.
+fn binomial(n: usize, k: usize) -> usize {
+    crate::checked_combination(n, k).unwrap()
+}
+
+/// Returns the set elements of a multiset.
+fn multiset_to_set(xs: &[usize]) -> impl Iterator<Item = Option<usize>> + '_ {
+    xs.iter().enumerate().map(|(i, &x)| x.checked_add(i))
+}
+
+/// Converts a set to a multiset in place.
+fn set_to_multiset(xs: &mut [usize]) {
+    for (i, x) in xs.iter_mut().enumerate() {
+        *x -= i;
+    }
+}
+
+/// Returns the set elements of a list.
+fn list_to_set(xs: &[usize]) -> impl Iterator<Item = Option<usize>> + '_ {
+    xs.iter().enumerate().scan(Some(0usize), |sum, (i, &x)| {
+        *sum = sum.and_then(|s| s.checked_add(x));
+        Some(sum.and_then(|s| s.checked_add(i)))
+    })
+}
+
+/// Converts a set to a list in place.
+fn set_to_list(xs: &mut [usize]) {
+    for i in (1 .. xs.len()).rev() {
+        xs[i] -= xs[i - 1] + 1;
+    }
+}
+
+fn encode_bits(ys: impl Iterator<Item = Option<usize>>) -> Option<usize> {
+    let mut s = 0;
+    for y in ys {
+        let y = y?;
+        if y >= BITS {
+            return None;
+        }
+        s |= 1 << y;
+    }
+    Some(s)
+}
+
+fn decode_bits_mut(mut s: usize, xs: &mut [usize]) {
+    debug_assert_eq!(xs.len(), s.count_ones() as usize, "Failed precondition");
+    for x in xs {
+        *x = s.trailing_zeros() as usize;
+        s &= s - 1;
+    }
+}
+
+fn encode_graded(ys: impl Iterator<Item = Option<usize>>) -> Option<usize> {
+    // The elements are distinct and less than BITS.
+    let mut xs = [0; BITS];
+    let mut k = 0;
+    for y in ys {
+        let y = y?;
+        if y >= BITS {
+            return None;
+        }
+        xs[k] = y;
+        k += 1;
+    }
+    let Some((&m, xs)) = xs[.. k].split_last() else { return Some(0) };
+    let mut s = 1 << m;
+    for j in 0 .. k - 1 {
+        s += binomial(m, j);
+    }
+    Some(s + crate::combinadics::encode(xs))
+}
+
+/// Returns the length, the maximum, and the rank of the prefix in the combinatorial number system.
+fn decode_graded_parts(s: usize) -> (usize, usize, usize) {
+    if s == 0 {
+        return (0, 0, 0);
+    }
+    let m = s.ilog2() as usize;
+    let mut r = s - (1 << m);
+    let mut k = 1;
+    while r >= binomial(m, k - 1) {
+        r -= binomial(m, k - 1);
+        k += 1;
+    }
+    (k, m, r)
+}
+
+fn decode_graded_mut(s: usize, xs: &mut [usize]) {
+    let (k, m, r) = decode_graded_parts(s);
+    debug_assert_eq!(xs.len(), k, "Failed precondition");
+    let Some((last, xs)) = xs.split_last_mut() else { return };
+    *last = m;
+    crate::combinadics::decode_mut(r, k - 1, xs);
+}
+
+/// Returns the length of the set (or multiset or list) of a value.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_len;
+/// assert_eq!(decode_len(0), 0);
+/// assert_eq!(decode_len(0b110010), 3);
+/// ```
+pub fn decode_len(s: usize) -> usize {
+    s.count_ones() as usize
+}
+
+/// Writes the set of a value to a slice.
+///
+/// The written set can be encoded with [`encode_set`] to get back `s`.
+///
+/// See [`decode_set`] for a version that allocates a vector for the set.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s)`.
+pub fn decode_set_mut(s: usize, xs: &mut [usize]) {
+    decode_bits_mut(s, xs);
+}
+
+/// Returns the set of a value.
+///
+/// The returned set can be encoded with [`encode_set`] to get back `s`.
+///
+/// See [`decode_set_mut`] for a version that writes the set to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_set;
+/// assert_eq!(decode_set(0), &[]);
+/// assert_eq!(decode_set(0b110010), &[1, 4, 5]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_set(s: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_len(s)];
+    decode_set_mut(s, &mut xs);
+    xs
+}
+
+/// Returns the value of a set.
+///
+/// The returned value can be decoded with [`decode_set`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::encode_set;
+/// assert_eq!(encode_set(&[]), Some(0));
+/// assert_eq!(encode_set(&[1, 4, 5]), Some(0b110010));
+/// assert_eq!(encode_set(&[usize::BITS as usize]), None);
+/// ```
+pub fn encode_set(xs: &[usize]) -> Option<usize> {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    encode_bits(xs.iter().map(|&x| Some(x)))
+}
+
+#[test]
+fn set_ok() {
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode_set(s);
+        assert!(crate::is_ordered_set(&xs), "s={s}");
+        assert_eq!(encode_set(&xs), Some(s), "s={s}");
+    }
+}
+
+/// Writes the multiset of a value to a slice.
+///
+/// The written multiset can be encoded with [`encode_multiset`] to get back `s`.
+///
+/// See [`decode_multiset`] for a version that allocates a vector for the multiset.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s)`.
+pub fn decode_multiset_mut(s: usize, xs: &mut [usize]) {
+    decode_bits_mut(s, xs);
+    set_to_multiset(xs);
+}
+
+/// Returns the multiset of a value.
+///
+/// The returned multiset can be encoded with [`encode_multiset`] to get back `s`.
+///
+/// See [`decode_multiset_mut`] for a version that writes the multiset to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_multiset;
+/// assert_eq!(decode_multiset(0), &[]);
+/// assert_eq!(decode_multiset(0b100110), &[1, 1, 3]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_multiset(s: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_len(s)];
+    decode_multiset_mut(s, &mut xs);
+    xs
+}
+
+/// Returns the value of a multiset.
+///
+/// The returned value can be decoded with [`decode_multiset`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not non-decreasing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::encode_multiset;
+/// assert_eq!(encode_multiset(&[]), Some(0));
+/// assert_eq!(encode_multiset(&[1, 1, 3]), Some(0b100110));
+/// ```
+pub fn encode_multiset(xs: &[usize]) -> Option<usize> {
+    debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+    encode_bits(multiset_to_set(xs))
+}
+
+#[test]
+fn multiset_ok() {
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode_multiset(s);
+        assert!(crate::is_ordered_multiset(&xs), "s={s}");
+        assert_eq!(encode_multiset(&xs), Some(s), "s={s}");
+    }
+    assert_eq!(encode_multiset(&[0; usize::BITS as usize]), Some(usize::MAX));
+    assert_eq!(encode_multiset(&[0; usize::BITS as usize + 1]), None);
+}
+
+/// Writes the list of a value to a slice.
+///
+/// The written list can be encoded with [`encode_list`] to get back `s`.
+///
+/// See [`decode_list`] for a version that allocates a vector for the list.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s)`.
+pub fn decode_list_mut(s: usize, xs: &mut [usize]) {
+    decode_bits_mut(s, xs);
+    set_to_list(xs);
+}
+
+/// Returns the list of a value.
+///
+/// The returned list can be encoded with [`encode_list`] to get back `s`.
+///
+/// See [`decode_list_mut`] for a version that writes the list to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_list;
+/// assert_eq!(decode_list(0), &[]);
+/// assert_eq!(decode_list(0b100110), &[1, 0, 2]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_list(s: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_len(s)];
+    decode_list_mut(s, &mut xs);
+    xs
+}
+
+/// Returns the value of a list.
+///
+/// The returned value can be decoded with [`decode_list`] to get back `xs`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::encode_list;
+/// assert_eq!(encode_list(&[]), Some(0));
+/// assert_eq!(encode_list(&[1, 0, 2]), Some(0b100110));
+/// assert_eq!(encode_list(&[0, usize::MAX]), None);
+/// ```
+pub fn encode_list(xs: &[usize]) -> Option<usize> {
+    encode_bits(list_to_set(xs))
+}
+
+#[test]
+fn list_ok() {
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode_list(s);
+        assert_eq!(encode_list(&xs), Some(s), "s={s}");
+    }
+}
+
+/// Returns the length of the set (or multiset or list) of a value in graded order.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_len_graded;
+/// assert_eq!(decode_len_graded(0), 0);
+/// assert_eq!(decode_len_graded(4), 1);
+/// assert_eq!(decode_len_graded(5), 2);
+/// assert_eq!(decode_len_graded(7), 3);
+/// ```
+pub fn decode_len_graded(s: usize) -> usize {
+    decode_graded_parts(s).0
+}
+
+/// Writes the set of a value in graded order to a slice.
+///
+/// The written set can be encoded with [`encode_set_graded`] to get back `s`.
+///
+/// See [`decode_set_graded`] for a version that allocates a vector for the set.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len_graded(s)`.
+pub fn decode_set_graded_mut(s: usize, xs: &mut [usize]) {
+    decode_graded_mut(s, xs);
+}
+
+/// Returns the set of a value in graded order.
+///
+/// The returned set can be encoded with [`encode_set_graded`] to get back `s`.
+///
+/// See [`decode_set_graded_mut`] for a version that writes the set to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_set_graded;
+/// assert_eq!(decode_set_graded(0), &[]);
+/// assert_eq!(decode_set_graded(1), &[0]);
+/// assert_eq!(decode_set_graded(2), &[1]);
+/// assert_eq!(decode_set_graded(3), &[0, 1]);
+/// assert_eq!(decode_set_graded(4), &[2]);
+/// assert_eq!(decode_set_graded(5), &[0, 2]);
+/// assert_eq!(decode_set_graded(6), &[1, 2]);
+/// assert_eq!(decode_set_graded(7), &[0, 1, 2]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_set_graded(s: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_len_graded(s)];
+    decode_set_graded_mut(s, &mut xs);
+    xs
+}
+
+/// Returns the value of a set in graded order.
+///
+/// The returned value can be decoded with [`decode_set_graded`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::encode_set_graded;
+/// assert_eq!(encode_set_graded(&[]), Some(0));
+/// assert_eq!(encode_set_graded(&[1, 2]), Some(6));
+/// assert_eq!(encode_set_graded(&[3]), Some(8));
+/// ```
+pub fn encode_set_graded(xs: &[usize]) -> Option<usize> {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    encode_graded(xs.iter().map(|&x| Some(x)))
+}
+
+#[test]
+fn set_graded_ok() {
+    fn key(xs: &[usize]) -> (Option<usize>, usize, usize) {
+        (xs.last().copied(), xs.len(), crate::combinadics::encode(xs))
+    }
+    let mut sets: Vec<Vec<usize>> = (0 .. 1 << 10).map(decode_set).collect();
+    sets.sort_by_key(|xs| key(xs));
+    for (s, xs) in sets.iter().enumerate() {
+        assert_eq!(&decode_set_graded(s), xs, "s={s}");
+        assert_eq!(encode_set_graded(xs), Some(s), "s={s}");
+    }
+    let middle = usize::MAX / 4 * 3;
+    for s in (middle - 1000 .. middle + 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode_set_graded(s);
+        assert!(crate::is_ordered_set(&xs), "s={s}");
+        assert_eq!(encode_set_graded(&xs), Some(s), "s={s}");
+    }
+    assert_eq!(encode_set_graded(&[usize::BITS as usize]), None);
+}
+
+/// Writes the multiset of a value in graded order to a slice.
+///
+/// The written multiset can be encoded with [`encode_multiset_graded`] to get back `s`.
+///
+/// See [`decode_multiset_graded`] for a version that allocates a vector for the multiset.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len_graded(s)`.
+pub fn decode_multiset_graded_mut(s: usize, xs: &mut [usize]) {
+    decode_graded_mut(s, xs);
+    set_to_multiset(xs);
+}
+
+/// Returns the multiset of a value in graded order.
+///
+/// The returned multiset can be encoded with [`encode_multiset_graded`] to get back `s`.
+///
+/// See [`decode_multiset_graded_mut`] for a version that writes the multiset to a provided
+/// slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_multiset_graded;
+/// assert_eq!(decode_multiset_graded(0), &[]);
+/// assert_eq!(decode_multiset_graded(1), &[0]);
+/// assert_eq!(decode_multiset_graded(2), &[1]);
+/// assert_eq!(decode_multiset_graded(3), &[0, 0]);
+/// assert_eq!(decode_multiset_graded(7), &[0, 0, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_multiset_graded(s: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_len_graded(s)];
+    decode_multiset_graded_mut(s, &mut xs);
+    xs
+}
+
+/// Returns the value of a multiset in graded order.
+///
+/// The returned value can be decoded with [`decode_multiset_graded`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not non-decreasing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::encode_multiset_graded;
+/// assert_eq!(encode_multiset_graded(&[]), Some(0));
+/// assert_eq!(encode_multiset_graded(&[0, 0]), Some(3));
+/// ```
+pub fn encode_multiset_graded(xs: &[usize]) -> Option<usize> {
+    debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+    encode_graded(multiset_to_set(xs))
+}
+
+#[test]
+fn multiset_graded_ok() {
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode_multiset_graded(s);
+        assert!(crate::is_ordered_multiset(&xs), "s={s}");
+        assert_eq!(encode_multiset_graded(&xs), Some(s), "s={s}");
+    }
+}
+
+/// Writes the list of a value in graded order to a slice.
+///
+/// The written list can be encoded with [`encode_list_graded`] to get back `s`.
+///
+/// See [`decode_list_graded`] for a version that allocates a vector for the list.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len_graded(s)`.
+pub fn decode_list_graded_mut(s: usize, xs: &mut [usize]) {
+    decode_graded_mut(s, xs);
+    set_to_list(xs);
+}
+
+/// Returns the list of a value in graded order.
+///
+/// The returned list can be encoded with [`encode_list_graded`] to get back `s`.
+///
+/// See [`decode_list_graded_mut`] for a version that writes the list to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::decode_list_graded;
+/// assert_eq!(decode_list_graded(0), &[]);
+/// assert_eq!(decode_list_graded(1), &[0]);
+/// assert_eq!(decode_list_graded(2), &[1]);
+/// assert_eq!(decode_list_graded(3), &[0, 0]);
+/// assert_eq!(decode_list_graded(4), &[2]);
+/// assert_eq!(decode_list_graded(5), &[0, 1]);
+/// assert_eq!(decode_list_graded(6), &[1, 0]);
+/// assert_eq!(decode_list_graded(7), &[0, 0, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_list_graded(s: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_len_graded(s)];
+    decode_list_graded_mut(s, &mut xs);
+    xs
+}
+
+/// Returns the value of a list in graded order.
+///
+/// The returned value can be decoded with [`decode_list_graded`] to get back `xs`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::finite::encode_list_graded;
+/// assert_eq!(encode_list_graded(&[]), Some(0));
+/// assert_eq!(encode_list_graded(&[1, 0]), Some(6));
+/// ```
+pub fn encode_list_graded(xs: &[usize]) -> Option<usize> {
+    encode_graded(list_to_set(xs))
+}
+
+#[test]
+fn list_graded_ok() {
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode_list_graded(s);
+        assert_eq!(encode_list_graded(&xs), Some(s), "s={s}");
+    }
+    // Lists are ordered by sum plus length first.
+    let mut previous = 0;
+    for s in 0 .. 1 << 12 {
+        let xs = decode_list_graded(s);
+        let grade = xs.iter().sum::<usize>() + xs.len();
+        assert!(previous <= grade, "s={s}");
+        previous = grade;
+    }
+}
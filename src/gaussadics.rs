@@ -0,0 +1,618 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gaussian binomial number system
+//!
+//! The weight of a k-combination `xs` (an increasing slice) is the sum of `xs[i] - i`. The values
+//! `xs[i] - i` form a partition of the weight in at most `k` parts, and the k-combinations of a set
+//! of `n` elements are exactly those partitions fitting in a box of `k` rows and `n - k` columns.
+//! The number of such partitions for a given weight is given by [`gaussian_binomial`].
+//!
+//! This module ranks the k-combinations of a given weight in value order (the same order as
+//! [`combinadics`](crate::combinadics)). The ranks do not depend on `n`: the k-combinations of a
+//! set of `n` elements with a given weight are a prefix of all k-combinations with that weight.
+//!
+//! Counting the k-combinations of a weight of 256 or more needs a table proportional to the
+//! weight. Without the `alloc` feature, encoding and decoding may thus panic for such weights.
+//!
+//! See [wikipedia] for more information.
+//!
+//! [`gaussian_binomial`]: crate::gaussian_binomial
+//! [wikipedia]: https://en.wikipedia.org/wiki/Gaussian_binomial_coefficient
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::BorrowMut;
+
+use crate::iter::{Lending, RankedIter};
+
+/// Returns the weight of a k-combination.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::gaussadics::weight;
+/// assert_eq!(weight(&[0, 1, 2]), 0);
+/// assert_eq!(weight(&[0, 1, 3]), 1);
+/// assert_eq!(weight(&[1, 3, 4]), 5);
+/// ```
+pub fn weight(xs: &[usize]) -> usize {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    xs.iter().enumerate().map(|(i, &x)| x - i).sum()
+}
+
+#[test]
+fn weight_ok() {
+    fn test(xs: &[usize], w: usize) {
+        assert_eq!(weight(xs), w, "xs={xs:?}");
+    }
+    test(&[], 0);
+    test(&[0], 0);
+    test(&[3], 3);
+    test(&[0, 1], 0);
+    test(&[1, 2], 2);
+    test(&[0, 3], 2);
+    test(&[2, 5, 6], 10);
+}
+
+/// Number of coefficients of the table used by [`count`] without allocation.
+const TABLE: usize = 256;
+
+/// Returns the number of k-combinations of a set of `n` elements with weight `w`.
+///
+/// This is the number of partitions of `w` fitting in a box of `k` rows and `n - k` columns. This
+/// takes time proportional to `k * w`.
+///
+/// # Panics
+///
+/// Panics without the `alloc` feature if the table does not fit on the stack.
+fn count(n: usize, k: usize, w: usize) -> usize {
+    if n < k {
+        return 0;
+    }
+    // The count is invariant by transposing the box and by complementing the partition in the box.
+    let (k, m) = (k.min(n - k), k.max(n - k));
+    let w = match k.checked_mul(m) {
+        Some(area) if area < w => return 0,
+        Some(area) => w.min(area - w),
+        None => w,
+    };
+    match k {
+        0 => (w == 0) as usize,
+        1 => 1,
+        2 => (w / 2 + 1).saturating_sub(w.saturating_sub(m)),
+        _ if w < TABLE => {
+            let mut r = [0; TABLE];
+            crate::gaussian_binomial_prefix(k + m, k, &mut r[..= w]);
+            r[w]
+        }
+        #[cfg(feature = "alloc")]
+        _ => {
+            let mut r = vec![0; w + 1];
+            crate::gaussian_binomial_prefix(k + m, k, &mut r);
+            r[w]
+        }
+        #[cfg(not(feature = "alloc"))]
+        _ => panic!("Weight too large without allocation"),
+    }
+}
+
+#[test]
+fn count_ok() {
+    for n in 0 .. 12 {
+        for k in 0 ..= n + 1 {
+            let r = crate::gaussian_binomial(n, k);
+            for w in 0 .. r.len() + 2 {
+                assert_eq!(count(n, k, w), r.get(w).copied().unwrap_or(0), "n={n} k={k} w={w}");
+            }
+        }
+    }
+    // Large weights use an allocated table.
+    for (n, k, w) in [(300, 3, 300), (400, 4, 350), (40, 20, 300)] {
+        let mut r = vec![0; w + 1];
+        crate::gaussian_binomial_prefix(n, k, &mut r);
+        assert_eq!(count(n, k, w), r[w], "n={n} k={k} w={w}");
+    }
+}
+
+/// Writes the k-combination of weight `w` of a value to a slice.
+///
+/// The written k-combination can be encoded with [`encode`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::gaussadics::{decode_mut, encode};
+/// # let p = 2;
+/// # let k = 3;
+/// # let w = 4;
+/// let mut xs = vec![0; k];
+/// decode_mut(p, k, w, &mut xs);
+/// assert_eq!(encode(&xs), p);
+/// ```
+///
+/// See [`decode`] for a version that allocates a vector for the k-combination.
+///
+/// # Panics
+///
+/// Panics in debug mode if `r.len() != k` or `p` is out of range, i.e. there are not more than `p`
+/// partitions of `w` in at most `k` parts. Without the `alloc` feature, may panic if `w >= 256`.
+pub fn decode_mut(mut p: usize, mut k: usize, mut w: usize, r: &mut [usize]) {
+    debug_assert_eq!(r.len(), k, "Failed precondition");
+    debug_assert!(p < count(k + w, k, w), "Failed precondition");
+    while k > 0 {
+        // Find the largest element, i.e. the largest i such that count(i, k, w) <= p.
+        let (mut i, mut j) = (k - 1, k + w);
+        while j - i > 1 {
+            let h = i + (j - i) / 2;
+            if count(h, k, w) <= p {
+                i = h;
+            } else {
+                j = h;
+            }
+        }
+        p -= count(i, k, w);
+        k -= 1;
+        w -= i - k;
+        r[k] = i;
+    }
+    debug_assert_eq!(p, 0);
+    debug_assert_eq!(w, 0);
+}
+
+/// Returns the k-combination of weight `w` of a value.
+///
+/// The returned k-combination can be encoded with [`encode`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::gaussadics::{decode, encode};
+/// let p = 2;
+/// let k = 3;
+/// let w = 4;
+/// let xs = decode(p, k, w);
+/// assert_eq!(encode(&xs), p);
+/// ```
+///
+/// See [`decode_mut`] for a version that writes the k-combination to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p` is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::gaussadics::decode;
+/// assert_eq!(decode(0, 3, 4), &[1, 2, 4]);
+/// assert_eq!(decode(1, 3, 4), &[0, 3, 4]);
+/// assert_eq!(decode(2, 3, 4), &[0, 2, 5]);
+/// assert_eq!(decode(3, 3, 4), &[0, 1, 6]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode(p: usize, k: usize, w: usize) -> Vec<usize> {
+    let mut r = vec![0; k];
+    decode_mut(p, k, w, &mut r);
+    r
+}
+
+#[test]
+fn decode_ok() {
+    fn test(p: usize, k: usize, w: usize, r: &[usize]) {
+        assert_eq!(decode(p, k, w), r, "p={p} k={k} w={w}");
+    }
+    test(0, 0, 0, &[]);
+    test(0, 1, 0, &[0]);
+    test(0, 1, 3, &[3]);
+    test(0, 2, 0, &[0, 1]);
+    test(0, 2, 1, &[0, 2]);
+    test(0, 2, 2, &[1, 2]);
+    test(1, 2, 2, &[0, 3]);
+    test(0, 2, 3, &[1, 3]);
+    test(1, 2, 3, &[0, 4]);
+    test(0, 3, 4, &[1, 2, 4]);
+    test(1, 3, 4, &[0, 3, 4]);
+    test(2, 3, 4, &[0, 2, 5]);
+    test(3, 3, 4, &[0, 1, 6]);
+    let n = count(3006, 6, 3000);
+    for p in [0, n / 2, n - 1] {
+        let xs = decode(p, 6, 3000);
+        assert_eq!((weight(&xs), encode(&xs)), (3000, p), "p={p}");
+    }
+}
+
+/// Returns the value of a k-combination among those of the same weight.
+///
+/// The returned value can be decoded with [`decode`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::gaussadics::{decode, encode, weight};
+/// # let xs = &[0, 2, 5];
+/// let k = xs.len();
+/// let w = weight(xs);
+/// let p = encode(xs);
+/// assert_eq!(decode(p, k, w), xs);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing. Without the `alloc` feature, may panic if the
+/// weight of `xs` is at least 256.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::gaussadics::encode;
+/// assert_eq!(encode(&[1, 2, 4]), 0);
+/// assert_eq!(encode(&[0, 3, 4]), 1);
+/// assert_eq!(encode(&[0, 2, 5]), 2);
+/// assert_eq!(encode(&[0, 1, 6]), 3);
+/// ```
+pub fn encode(xs: &[usize]) -> usize {
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    let mut w = 0;
+    let mut r = 0;
+    for (i, &x) in xs.iter().enumerate() {
+        w += x - i;
+        r += count(x, i + 1, w);
+    }
+    r
+}
+
+#[test]
+fn encode_ok() {
+    fn test(xs: &[usize], r: usize) {
+        assert_eq!(encode(xs), r, "xs={xs:?}");
+    }
+    test(&[], 0);
+    test(&[0], 0);
+    test(&[5], 0);
+    test(&[0, 1], 0);
+    test(&[1, 2], 0);
+    test(&[0, 3], 1);
+    test(&[2, 3], 0);
+    test(&[1, 4], 1);
+    test(&[0, 5], 2);
+    test(&[1, 2, 4], 0);
+    test(&[0, 3, 4], 1);
+    test(&[0, 2, 5], 2);
+    test(&[0, 1, 6], 3);
+}
+
+/// Returns the value of a k-combination of a set of `n` elements, graded by weight.
+///
+/// The k-combinations are ordered by weight first, then by value. The returned value is thus less
+/// than `combination(n, k)` and can be decoded with [`decode_graded`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::gaussadics::{decode_graded, encode_graded};
+/// # let n = 5;
+/// # let xs = &[0, 2, 4];
+/// let p = encode_graded(n, xs);
+/// assert_eq!(decode_graded(n, xs.len(), p), xs);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or contains an element not less than `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::gaussadics::encode_graded;
+/// assert_eq!(encode_graded(4, &[0, 1]), 0);
+/// assert_eq!(encode_graded(4, &[0, 2]), 1);
+/// assert_eq!(encode_graded(4, &[1, 2]), 2);
+/// assert_eq!(encode_graded(4, &[0, 3]), 3);
+/// assert_eq!(encode_graded(4, &[1, 3]), 4);
+/// assert_eq!(encode_graded(4, &[2, 3]), 5);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_graded(n: usize, xs: &[usize]) -> usize {
+    debug_assert!(xs.last().is_none_or(|&x| x < n), "Failed precondition");
+    let w = weight(xs);
+    crate::gaussian_binomial(n, xs.len())[.. w].iter().sum::<usize>() + encode(xs)
+}
+
+/// Returns the k-combination of a set of `n` elements of a value, graded by weight.
+///
+/// This is the inverse of [`encode_graded`].
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= combination(n, k)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::gaussadics::decode_graded;
+/// assert_eq!(decode_graded(4, 2, 2), &[1, 2]);
+/// assert_eq!(decode_graded(4, 2, 3), &[0, 3]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_graded(n: usize, k: usize, mut p: usize) -> Vec<usize> {
+    debug_assert!(p < crate::combination(n, k), "Failed precondition");
+    let mut w = 0;
+    for c in crate::gaussian_binomial(n, k) {
+        if p < c {
+            break;
+        }
+        p -= c;
+        w += 1;
+    }
+    decode(p, k, w)
+}
+
+#[test]
+fn graded_ok() {
+    for n in 0 .. 8 {
+        for k in 0 ..= n {
+            let mut prev = 0;
+            for p in 0 .. crate::combination(n, k) {
+                let xs = decode_graded(n, k, p);
+                assert_eq!(xs.len(), k);
+                assert!(xs.last().is_none_or(|&x| x < n), "n={n} k={k} p={p}");
+                assert!(weight(&xs) >= prev, "n={n} k={k} p={p}");
+                prev = weight(&xs);
+                assert_eq!(encode_graded(n, &xs), p, "n={n} k={k} p={p}");
+            }
+        }
+    }
+}
+
+/// Iterates over all k-combinations of a given weight.
+///
+/// The k-combinations are iterated in value order:
+///
+/// ```rust
+/// # use number_encoding::gaussadics::{Iter, encode};
+/// # let k = 3;
+/// # let w = 4;
+/// let mut iter = Iter::new(k, w);
+/// let mut i = 0;
+/// while let Some(xs) = iter.next() {
+///     assert_eq!(encode(xs), i);
+///     i += 1;
+/// }
+/// ```
+///
+/// # Examples
+///
+/// To iterate over all `k`-combinations of weight `w` in a set of `n` elements:
+///
+/// ```rust
+/// # use number_encoding::gaussadics::Iter;
+/// # use number_encoding::gaussian_binomial;
+/// # fn process(xs: &[usize]) {}
+/// # let n = 6;
+/// # let k = 3;
+/// # let w = 4;
+/// let mut iter = Iter::new(k, w);
+/// for _ in 0 .. gaussian_binomial(n, k)[w] {
+///     process(iter.next().unwrap());
+/// }
+/// ```
+///
+/// In a no-std environment, you can pass a buffer of size `K`:
+///
+/// ```rust
+/// # use number_encoding::gaussadics::Iter;
+/// # const K: usize = 3;
+/// # let w = 4;
+/// let mut buffer = [0usize; K];
+/// let mut iter = Iter::new_with_buffer(&mut buffer[..], w);
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct Iter<T: BorrowMut<[usize]>> {
+    data: T,
+    state: IterState,
+    rank: usize,
+}
+
+enum IterState {
+    New,
+    Running,
+    Done,
+}
+
+#[cfg(feature = "alloc")]
+impl Iter<Vec<usize>> {
+    /// Constructs an iterator.
+    pub fn new(k: usize, w: usize) -> Iter<Vec<usize>> {
+        Iter::new_with_buffer(vec![0; k], w)
+    }
+}
+
+impl<T: BorrowMut<[usize]>> Iter<T> {
+    /// Constructs an iterator with a buffer.
+    pub fn new_with_buffer(mut buffer: T, w: usize) -> Iter<T> {
+        let xs = buffer.borrow_mut();
+        let k = xs.len();
+        if k == 0 {
+            let state = if w == 0 { IterState::New } else { IterState::Done };
+            return Iter { data: buffer, state, rank: 0 };
+        }
+        let (q, m) = (w / k, w % k);
+        for (i, x) in xs.iter_mut().enumerate() {
+            *x = i + q + (i >= k - m) as usize;
+        }
+        Iter { data: buffer, state: IterState::New, rank: 0 }
+    }
+
+    /// Constructs an iterator starting from a given k-combination.
+    ///
+    /// The iterated k-combinations have the weight of `xs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not increasing. Without the `alloc` feature, may panic if
+    /// the weight of `xs` is at least 256.
+    pub fn new_from(xs: T) -> Iter<T> {
+        debug_assert!(crate::is_ordered_set(xs.borrow()), "Failed precondition");
+        let rank = encode(xs.borrow());
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Returns the value of the current k-combination.
+    ///
+    /// The current k-combination is the one last returned by [`next`](Iter::next), or the one it
+    /// will return if it was not called yet. After the end of the iteration, this is the number of
+    /// k-combinations of the weight.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the k-combination of a given value (with the same weight).
+    ///
+    /// The next call to [`next`](Iter::next) returns this k-combination.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range. Without the `alloc` feature, may panic if
+    /// the weight is at least 256.
+    pub fn seek(&mut self, rank: usize) {
+        let xs = self.data.borrow_mut();
+        let (k, w) = (xs.len(), weight(xs));
+        decode_mut(rank, k, w, xs);
+        self.state = IterState::New;
+        self.rank = rank;
+    }
+
+    /// Returns the next k-combination.
+    ///
+    /// If iteration is over, returns `None`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            IterState::New => self.state = IterState::Running,
+            IterState::Running => {
+                self.rank += 1;
+                if self.advance() {
+                    self.state = IterState::Done;
+                }
+            }
+            IterState::Done => (),
+        }
+        match self.state {
+            IterState::New => unreachable!(),
+            IterState::Running => Some(self.data.borrow()),
+            IterState::Done => None,
+        }
+    }
+
+    fn advance(&mut self) -> bool {
+        let xs = self.data.borrow_mut();
+        let k = xs.len();
+        let mut s = xs.first().copied().unwrap_or(0);
+        let mut u = 1;
+        while u < k {
+            if s > 0 && (u == k - 1 || xs[u] + 1 < xs[u + 1]) {
+                break;
+            }
+            s += xs[u] - u;
+            u += 1;
+        }
+        if u >= k {
+            return true;
+        }
+        xs[u] += 1;
+        let (q, m) = ((s - 1) / u, (s - 1) % u);
+        for (i, x) in xs[.. u].iter_mut().enumerate() {
+            *x = i + q + (i >= u - m) as usize;
+        }
+        false
+    }
+}
+
+impl<T: BorrowMut<[usize]>> Lending for Iter<T> {
+    type Item = usize;
+
+    fn lend(&mut self) -> Option<&[usize]> {
+        self.next()
+    }
+}
+
+impl<T: BorrowMut<[usize]>> RankedIter for Iter<T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
+    }
+}
+
+#[test]
+fn iter_ok() {
+    fn test(k: usize, w: usize, r: &[&[usize]]) {
+        let mut iter = Iter::new(k, w);
+        let mut i = 0;
+        while let Some(xs) = iter.next() {
+            assert_eq!(xs, r[i], "k={k} w={w}");
+            assert_eq!(encode(xs), i);
+            i += 1;
+        }
+        assert_eq!(r.len(), i, "k={k} w={w}");
+    }
+    test(0, 0, &[&[]]);
+    test(0, 1, &[]);
+    test(1, 0, &[&[0]]);
+    test(1, 2, &[&[2]]);
+    test(2, 0, &[&[0, 1]]);
+    test(2, 3, &[&[1, 3], &[0, 4]]);
+    test(2, 4, &[&[2, 3], &[1, 4], &[0, 5]]);
+    test(3, 4, &[&[1, 2, 4], &[0, 3, 4], &[0, 2, 5], &[0, 1, 6]]);
+    test(
+        3,
+        6,
+        &[&[2, 3, 4], &[1, 3, 5], &[0, 4, 5], &[1, 2, 6], &[0, 3, 6], &[0, 2, 7], &[0, 1, 8]],
+    );
+}
+
+#[test]
+fn iter_new_from_ok() {
+    fn test(xs: &[usize], r: &[&[usize]]) {
+        let mut iter = Iter::new_from(xs.to_vec());
+        let start = encode(xs);
+        let mut i = 0;
+        while let Some(xs) = iter.next() {
+            assert_eq!(xs, r[i]);
+            assert_eq!(encode(xs), start + i);
+            i += 1;
+        }
+        assert_eq!(r.len(), i);
+    }
+    test(&[], &[&[]]);
+    test(&[2], &[&[2]]);
+    test(&[0, 3, 4], &[&[0, 3, 4], &[0, 2, 5], &[0, 1, 6]]);
+}
+
+#[test]
+fn iter_rank_ok() {
+    let mut iter = Iter::new_from(vec![0, 1, 5]);
+    assert_eq!(iter.rank(), encode(&[0, 1, 5]));
+    while let Some(xs) = iter.next() {
+        let xs = xs.to_vec();
+        assert_eq!(encode(&xs), iter.rank());
+    }
+    assert_eq!(iter.rank(), crate::gaussian_binomial(6, 3)[3]);
+    iter.seek(2);
+    assert_eq!(iter.next(), Some(&decode(2, 3, 3)[..]));
+    assert_eq!(iter.rank(), 2);
+}
@@ -0,0 +1,430 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Standard iterators over enumerations
+//!
+//! The `Iter` types of this crate are lending iterators: they return a slice borrowing their
+//! buffer, which avoids allocation but does not work with `for` loops or iterator combinators.
+//! They implement [`Lending`], which permits to adapt them to standard iterators:
+//!
+//! - [`Lending::vecs`] yields each item as a vector.
+//! - [`Lending::arrays`] yields each item as an array.
+//! - [`Vecs::ranked`] and [`Arrays::ranked`] yield the rank of each item with the item.
+//!
+//! Some of them also implement [`LendingBack`], which permits to iterate backward with
+//! [`LendingBack::rev`], or from both ends at once with [`DoubleEnded`]:
+//!
+//! ```rust
+//! # use number_encoding::factoradics::Iter;
+//! use number_encoding::iter::{DoubleEnded, Lending, LendingBack};
+//! let mut xs = [0, 1, 2];
+//! let mut ys = [0, 1, 2];
+//! let mut iter = DoubleEnded::new(Iter::new(&mut xs), Iter::new_rev(&mut ys).rev());
+//! assert_eq!(iter.next(), Some(vec![0, 1, 2]));
+//! assert_eq!(iter.next_back(), Some(vec![2, 1, 0]));
+//! assert_eq!(iter.next_back(), Some(vec![2, 0, 1]));
+//! assert_eq!(iter.len(), 3);
+//! ```
+//!
+//! ```rust
+//! # use number_encoding::factoradics::Iter;
+//! use number_encoding::iter::Lending;
+//! let mut xs = [0, 1, 2];
+//! let ys: Vec<_> = Iter::new(&mut xs).vecs().ranked().skip(2).take(2).collect();
+//! assert_eq!(ys, [(2, vec![1, 0, 2]), (3, vec![1, 2, 0])]);
+//! ```
+//!
+//! The lending iterators should be preferred in hot loops.
+//!
+//! The `Iter` types also implement [`RankedIter`], which permits to query the rank of their current
+//! item and to move to the item of a given rank (e.g. to checkpoint and resume an iteration).
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Iterators returning slices borrowing their buffer.
+pub trait Lending {
+    /// The type of the elements of the items.
+    type Item;
+
+    /// Returns the next item.
+    ///
+    /// If iteration is over, returns `None`.
+    fn lend(&mut self) -> Option<&[Self::Item]>;
+
+    /// Returns the bounds on the number of remaining items.
+    ///
+    /// This has the same semantics as [`Iterator::size_hint`].
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    /// Returns an iterator cloning the items to vectors.
+    #[cfg(feature = "alloc")]
+    fn vecs(self) -> Vecs<Self>
+    where
+        Self: Sized,
+    {
+        Vecs(self)
+    }
+
+    /// Returns an iterator cloning the items to arrays.
+    ///
+    /// The iterator panics if an item does not have length `N`.
+    fn arrays<const N: usize>(self) -> Arrays<Self, N>
+    where
+        Self: Sized,
+    {
+        Arrays(self)
+    }
+}
+
+/// Lending iterators that can also iterate backward.
+pub trait LendingBack: Lending {
+    /// Returns the previous item.
+    ///
+    /// The first call returns the current item. If iteration is over, returns `None`.
+    fn lend_back(&mut self) -> Option<&[Self::Item]>;
+
+    /// Returns a lending iterator iterating backward.
+    fn rev(self) -> Rev<Self>
+    where
+        Self: Sized,
+    {
+        Rev(self)
+    }
+}
+
+/// Iterates backward over the items of a lending iterator.
+///
+/// See [`LendingBack::rev`].
+pub struct Rev<I>(I);
+
+impl<I: LendingBack> Lending for Rev<I> {
+    type Item = I::Item;
+
+    fn lend(&mut self) -> Option<&[I::Item]> {
+        self.0.lend_back()
+    }
+}
+
+impl<I: LendingBack + RankedIter> RankedIter for Rev<I> {
+    fn rank(&self) -> usize {
+        self.0.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.0.seek(rank)
+    }
+}
+
+/// Iterators with a current item of known rank.
+///
+/// For iterators with a `get` method, the current item is the one returned by `get`. For iterators
+/// with a `next` method, the current item is the one last returned by `next`, or the one `next`
+/// will return if it was not called yet. After the end of the iteration, the rank is one past the
+/// last rank.
+pub trait RankedIter {
+    /// Returns the rank of the current item.
+    ///
+    /// This takes constant time.
+    fn rank(&self) -> usize;
+
+    /// Moves to the item of a given rank.
+    ///
+    /// For iterators with a `next` method, the next call returns this item.
+    fn seek(&mut self, rank: usize);
+}
+
+/// Iterates over the items of a lending iterator as vectors.
+///
+/// See [`Lending::vecs`].
+#[cfg(feature = "alloc")]
+pub struct Vecs<I>(I);
+
+#[cfg(feature = "alloc")]
+impl<I: Lending> Vecs<I> {
+    /// Returns an iterator yielding the rank of each item with the item.
+    pub fn ranked(self) -> Ranked<Self> {
+        Ranked { iter: self }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Lending> Iterator for Vecs<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        self.0.lend().map(|xs| xs.to_vec())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterates over the items of a lending iterator as arrays.
+///
+/// See [`Lending::arrays`].
+pub struct Arrays<I, const N: usize>(I);
+
+impl<I: Lending, const N: usize> Arrays<I, N> {
+    /// Returns an iterator yielding the rank of each item with the item.
+    pub fn ranked(self) -> Ranked<Self> {
+        Ranked { iter: self }
+    }
+}
+
+impl<I: Lending, const N: usize> Iterator for Arrays<I, N>
+where
+    I::Item: Clone,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<[I::Item; N]> {
+        let xs = self.0.lend()?;
+        assert_eq!(xs.len(), N, "Item length mismatch");
+        Some(core::array::from_fn(|i| xs[i].clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterates over the items of two lending iterators from both ends as vectors.
+///
+/// The first iterator provides the items from the front and the second iterator provides the
+/// items from the back (e.g. using [`Rev`]). The iteration ends after the items between their
+/// current ranks (inclusive), such that each item is returned exactly once.
+#[cfg(feature = "alloc")]
+pub struct DoubleEnded<F, B> {
+    front: F,
+    back: B,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<F: RankedIter, B: RankedIter> DoubleEnded<F, B> {
+    /// Constructs an iterator from both ends of an iteration.
+    ///
+    /// Both iterators must not have started, and their current items are the first and last items
+    /// of the iteration. The iteration is empty if the rank of `back` is smaller than the rank of
+    /// `front`.
+    pub fn new(front: F, back: B) -> DoubleEnded<F, B> {
+        let len = (back.rank() + 1).saturating_sub(front.rank());
+        DoubleEnded { front, back, len }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<F: Lending, B: Lending<Item = F::Item>> Iterator for DoubleEnded<F, B>
+where
+    F::Item: Clone,
+{
+    type Item = Vec<F::Item>;
+
+    fn next(&mut self) -> Option<Vec<F::Item>> {
+        self.len = self.len.checked_sub(1)?;
+        self.front.lend().map(|xs| xs.to_vec())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<F: Lending, B: Lending<Item = F::Item>> DoubleEndedIterator for DoubleEnded<F, B>
+where
+    F::Item: Clone,
+{
+    fn next_back(&mut self) -> Option<Vec<F::Item>> {
+        self.len = self.len.checked_sub(1)?;
+        self.back.lend().map(|xs| xs.to_vec())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<F: Lending, B: Lending<Item = F::Item>> ExactSizeIterator for DoubleEnded<F, B> where
+    F::Item: Clone
+{
+}
+
+/// Iterates over the items of an iterator with their rank.
+///
+/// The rank of each item is the [rank](RankedIter::rank) of the iterator after lending it.
+///
+/// See [`Vecs::ranked`] and [`Arrays::ranked`].
+pub struct Ranked<J> {
+    iter: J,
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Lending + RankedIter> Iterator for Ranked<Vecs<I>>
+where
+    I::Item: Clone,
+{
+    type Item = (usize, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<(usize, Vec<I::Item>)> {
+        let xs = self.iter.next()?;
+        Some((self.iter.0.rank(), xs))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: Lending + RankedIter, const N: usize> Iterator for Ranked<Arrays<I, N>>
+where
+    I::Item: Clone,
+{
+    type Item = (usize, [I::Item; N]);
+
+    fn next(&mut self) -> Option<(usize, [I::Item; N])> {
+        let xs = self.iter.next()?;
+        Some((self.iter.0.rank(), xs))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[test]
+fn vecs_ok() {
+    use crate::{combinadics, factoradics, gaussadics, multinadics, necklaces};
+    let mut xs = [0, 1, 2, 3];
+    let ys: Vec<_> = factoradics::Iter::new(&mut xs).vecs().collect();
+    assert_eq!(ys.len(), 24);
+    for (p, ys) in ys.iter().enumerate() {
+        assert_eq!(ys, &factoradics::decode(&xs, p));
+    }
+    let mut xs = [0, 0, 1, 1, 2];
+    let ys: Vec<_> = multinadics::Iter::new(&mut xs).vecs().collect();
+    assert_eq!(ys.len(), 30);
+    for (p, ys) in ys.iter().enumerate() {
+        assert_eq!(ys, &multinadics::decode(&xs, p));
+    }
+    for (p, ys) in combinadics::Iter::new(3).vecs().take(100).enumerate() {
+        assert_eq!(ys, combinadics::decode(p, 3));
+    }
+    let ys: Vec<_> = gaussadics::Iter::new(3, 4).vecs().collect();
+    for (p, ys) in ys.iter().enumerate() {
+        assert_eq!(ys, &gaussadics::decode(p, 3, 4));
+    }
+    let ys: Vec<_> = necklaces::Iter::new(4, 3).vecs().collect();
+    assert_eq!(ys.len(), crate::necklace(4, 3));
+    for (p, ys) in ys.iter().enumerate() {
+        assert_eq!(ys, &necklaces::decode(p, 4, 3));
+    }
+}
+
+#[test]
+fn gray_ok() {
+    use crate::sequences::gray;
+    for n in 0 .. 6 {
+        let ys: Vec<_> = gray::Iter::new(n).vecs().collect();
+        assert_eq!(ys.len(), 1 << n, "n={n}");
+        for (p, ys) in ys.iter().enumerate() {
+            assert_eq!(ys, &gray::decode((1 << n) - 1 + p), "n={n}");
+        }
+    }
+    let ys: Vec<_> = gray::Iter::new_from([true, true].to_vec()).vecs().ranked().collect();
+    assert_eq!(ys, [(2, [true, true].to_vec()), (3, [true, false].to_vec())]);
+}
+
+#[test]
+fn arrays_ok() {
+    use crate::{combinadics, factoradics};
+    let mut xs = [0, 1, 2];
+    let ys: Vec<[usize; 3]> = factoradics::Iter::new(&mut xs).arrays().collect();
+    assert_eq!(ys, [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]]);
+    let mut buffer = [0, 2];
+    let iter = combinadics::Iter::new_from(&mut buffer[..]);
+    let ys: Vec<_> = iter.arrays::<2>().ranked().take(3).collect();
+    assert_eq!(ys, [(1, [0, 2]), (2, [1, 2]), (3, [0, 3])]);
+}
+
+#[test]
+fn ranked_ok() {
+    use crate::{gaussadics, multinadics, necklaces};
+    let mut xs = [0, 0, 1, 2];
+    for (p, ys) in multinadics::Iter::new(&mut xs).vecs().ranked() {
+        assert_eq!(multinadics::encode(&ys), p);
+    }
+    let iter = gaussadics::Iter::new_from([0, 2, 5].to_vec());
+    for (p, ys) in iter.vecs().ranked() {
+        assert_eq!(gaussadics::encode(&ys), p);
+    }
+    for (p, ys) in necklaces::Iter::new(5, 2).vecs().ranked() {
+        assert_eq!(necklaces::encode(&ys, 2), p);
+    }
+}
+
+#[test]
+fn rev_ok() {
+    use crate::{combinadics, factoradics, multinadics};
+    let mut xs = [0, 1, 2, 3];
+    let ys: Vec<_> = factoradics::Iter::new_rev(&mut xs).rev().vecs().ranked().collect();
+    assert_eq!(ys.len(), 24);
+    for (p, ys) in ys {
+        assert_eq!(factoradics::encode(&ys), p);
+    }
+    assert_eq!(xs, [0, 1, 2, 3]);
+    let mut xs = [0, 0, 1, 2];
+    let ys: Vec<_> = multinadics::Iter::new_rev(&mut xs).rev().vecs().ranked().collect();
+    assert_eq!(ys.len(), 12);
+    for (i, (p, ys)) in ys.into_iter().enumerate() {
+        assert_eq!(p, 11 - i);
+        assert_eq!(multinadics::encode(&ys), p);
+    }
+    let ys: Vec<_> = combinadics::Iter::new_at_rank(3, 9).rev().vecs().collect();
+    let zs: Vec<_> = combinadics::Iter::new_bounded(5, 3).vecs().collect();
+    assert!(ys.into_iter().eq(zs.into_iter().rev()));
+}
+
+#[test]
+fn double_ended_ok() {
+    use crate::{combinadics, factoradics};
+    let mut xs = [0, 1, 2, 3];
+    let mut ys = [0, 1, 2, 3];
+    let front = factoradics::Iter::new(&mut xs);
+    let back = factoradics::Iter::new_rev(&mut ys).rev();
+    let mut iter = DoubleEnded::new(front, back);
+    assert_eq!(iter.len(), 24);
+    let mut r = Vec::new();
+    while let Some(xs) = iter.next() {
+        r.push(xs);
+        if let Some(xs) = iter.next_back() {
+            r.push(xs);
+        }
+    }
+    r.sort();
+    assert_eq!(r, factoradics::Iter::new(&mut [0, 1, 2, 3]).vecs().collect::<Vec<_>>());
+    let front = combinadics::Iter::new(2);
+    let back = combinadics::Iter::new_at_rank(2, 9).rev();
+    let r: Vec<_> = DoubleEnded::new(front, back).rev().collect();
+    let mut s: Vec<_> = combinadics::Iter::new_bounded(5, 2).vecs().collect();
+    s.reverse();
+    assert_eq!(r, s);
+    let front = combinadics::Iter::new_at_rank(2, 4);
+    let back = combinadics::Iter::new_at_rank(2, 3).rev();
+    assert_eq!(DoubleEnded::new(front, back).count(), 0);
+}
@@ -14,8 +14,12 @@
 
 //! Number systems
 //!
-//! This crate provides number systems for combinations, factorials, multinomials, and sequences of
-//! bits.
+//! This crate provides number systems for combinations, pairs, combinations graded by weight,
+//! factorials, multinomials, necklaces, orbits under permutation groups, sequences of bits or
+//! letters, bit sequences without long runs of ones, positive rationals, finite sets, multisets, or
+//! lists of integers, and ordered trees or s-expressions. It also provides pairing functions
+//! between tuples and integers, space-filling curves between grid points and integers, and
+//! universal codes to write integers to bit buffers.
 
 #![no_std]
 #![warn(unused_results, missing_docs)]
@@ -26,9 +30,26 @@ extern crate alloc;
 extern crate std;
 
 pub mod combinadics;
+pub mod curves;
 pub mod factoradics;
+pub mod finite;
+pub mod gaussadics;
+pub mod iter;
 pub mod multinadics;
+pub mod necklaces;
+#[cfg(feature = "alloc")]
+pub mod orbits;
+pub mod pairing;
+pub mod pairs;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod rationals;
 pub mod sequences;
+#[cfg(feature = "alloc")]
+pub mod shards;
+pub mod trees;
+pub mod universal;
+pub mod zeckendorf;
 
 /// Returns the greatest common divisor of `a` and `b`.
 ///
@@ -119,6 +140,24 @@ fn factorial_ok() {
     }
 }
 
+/// Returns the factorial of `n`.
+///
+/// Returns `None` if the number overflows.
+#[cfg(feature = "alloc")]
+fn checked_factorial(n: usize) -> Option<usize> {
+    (1 ..= n).try_fold(1usize, |r, i| r.checked_mul(i))
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn checked_factorial_ok() {
+    for n in 0 .. 20 {
+        assert_eq!(checked_factorial(n), Some(factorial(n)), "n={n}");
+    }
+    assert_eq!(checked_factorial(20), Some(2432902008176640000));
+    assert_eq!(checked_factorial(21), None);
+}
+
 /// Returns the number of `k`-combinations of a set of `n` elements.
 ///
 /// See [wikipedia] for more information.
@@ -136,23 +175,12 @@ fn factorial_ok() {
 /// ```
 ///
 /// [wikipedia]: https://en.wikipedia.org/wiki/Combination
+///
+/// # Panics
+///
+/// Panics if the number overflows.
 pub fn combination(n: usize, k: usize) -> usize {
-    if n < k {
-        return 0;
-    }
-    let mut r = 1;
-    let mut d = factorial(k);
-    for i in 0 .. k {
-        let mut m = n - i;
-        if d > 1 {
-            let g = greatest_common_divisor(m, d);
-            m /= g;
-            d /= g;
-        }
-        r *= m;
-    }
-    debug_assert_eq!(d, 1);
-    r
+    checked_combination(n, k).expect("Overflow")
 }
 
 #[test]
@@ -169,6 +197,353 @@ fn combination_ok() {
             assert_eq!(combination(n, k), spec(n, k), "n={n} k={k}");
         }
     }
+    assert_eq!(combination(21, 21), 1);
+    assert_eq!(combination(63, 31), 916312070471295267);
+}
+
+/// Returns the number of k-combinations of a set of `n` elements.
+///
+/// Returns `None` if the number overflows.
+fn checked_combination(n: usize, k: usize) -> Option<usize> {
+    if n < k {
+        return Some(0);
+    }
+    let mut r = 1u128;
+    for i in 0 .. k.min(n - k) {
+        r = r * (n - i) as u128 / (i + 1) as u128;
+        if r > usize::MAX as u128 {
+            return None;
+        }
+    }
+    Some(r as usize)
+}
+
+#[test]
+fn checked_combination_ok() {
+    for n in 0 .. 20 {
+        for k in 0 .. 20 {
+            assert_eq!(checked_combination(n, k), Some(combination(n, k)), "n={n} k={k}");
+        }
+    }
+    assert_eq!(checked_combination(100, 100), Some(1));
+    assert_eq!(checked_combination(100, 99), Some(100));
+    assert_eq!(checked_combination(67, 33), Some(14226520737620288370));
+    assert_eq!(checked_combination(68, 34), None);
+}
+
+/// Returns the coefficients of the Gaussian binomial coefficient of `n` and `k`.
+///
+/// The coefficient at index `w` is the number of `k`-combinations of a set of `n` elements whose
+/// weight is `w` (see [`gaussadics`] for the definition of weight). Equivalently, it is the number
+/// of partitions of `w` fitting in a box of `k` rows and `n - k` columns. The returned vector has
+/// length `k * (n - k) + 1`, or is empty if `n < k`. Its sum is `combination(n, k)`.
+///
+/// See [wikipedia] for more information.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::gaussian_binomial;
+/// assert_eq!(gaussian_binomial(4, 0), &[1]);
+/// assert_eq!(gaussian_binomial(4, 1), &[1, 1, 1, 1]);
+/// assert_eq!(gaussian_binomial(4, 2), &[1, 1, 2, 1, 1]);
+/// assert_eq!(gaussian_binomial(4, 5), &[]);
+/// ```
+///
+/// [wikipedia]: https://en.wikipedia.org/wiki/Gaussian_binomial_coefficient
+#[cfg(feature = "alloc")]
+pub fn gaussian_binomial(n: usize, k: usize) -> alloc::vec::Vec<usize> {
+    if n < k {
+        return alloc::vec::Vec::new();
+    }
+    let mut r = alloc::vec![0; k * (n - k) + 1];
+    gaussian_binomial_prefix(n, k, &mut r);
+    r
+}
+
+#[test]
+fn gaussian_binomial_ok() {
+    fn test(n: usize, k: usize, r: &[usize]) {
+        assert_eq!(gaussian_binomial(n, k), r, "n={n} k={k}");
+    }
+    test(0, 0, &[1]);
+    test(1, 0, &[1]);
+    test(1, 1, &[1]);
+    test(2, 1, &[1, 1]);
+    test(3, 1, &[1, 1, 1]);
+    test(4, 2, &[1, 1, 2, 1, 1]);
+    test(5, 2, &[1, 1, 2, 2, 2, 1, 1]);
+    test(6, 3, &[1, 1, 2, 3, 3, 3, 3, 2, 1, 1]);
+    test(2, 3, &[]);
+    for n in 0 .. 10 {
+        for k in 0 ..= n {
+            assert_eq!(gaussian_binomial(n, k).iter().sum::<usize>(), combination(n, k));
+        }
+    }
+}
+
+/// Writes the first coefficients of the Gaussian binomial coefficient of `n` and `k` to `r`.
+///
+/// This computes the product of `(1 - q^(n - k + i)) / (1 - q^i)` for `i` in `1 ..= k` as a power
+/// series truncated to `r.len()` coefficients. Intermediate results may overflow, which is fine
+/// because the arithmetic is modular and the final coefficients are non-negative.
+fn gaussian_binomial_prefix(n: usize, k: usize, r: &mut [usize]) {
+    r.fill(0);
+    if n < k || r.is_empty() {
+        return;
+    }
+    r[0] = 1;
+    let l = r.len();
+    for i in 1 ..= k {
+        for d in i .. l {
+            r[d] = r[d].wrapping_add(r[d - i]);
+        }
+        let j = n - k + i;
+        for d in (j .. l).rev() {
+            r[d] = r[d].wrapping_sub(r[d - j]);
+        }
+    }
+}
+
+#[test]
+fn gaussian_binomial_prefix_ok() {
+    fn test(n: usize, k: usize, l: usize, e: &[usize]) {
+        let mut r = [usize::MAX; 8];
+        gaussian_binomial_prefix(n, k, &mut r[.. l]);
+        assert_eq!(&r[.. l], e, "n={n} k={k} l={l}");
+    }
+    test(4, 2, 0, &[]);
+    test(4, 2, 3, &[1, 1, 2]);
+    test(4, 2, 7, &[1, 1, 2, 1, 1, 0, 0]);
+    test(2, 3, 2, &[0, 0]);
+}
+
+/// Returns the number of necklaces of length `n` over an alphabet of `k` letters.
+///
+/// A necklace is an equivalence class of words under rotation.
+///
+/// See [wikipedia] for more information.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklace;
+/// assert_eq!(necklace(0, 2), 1);
+/// assert_eq!(necklace(3, 2), 4);
+/// assert_eq!(necklace(4, 2), 6);
+/// assert_eq!(necklace(4, 3), 24);
+/// ```
+///
+/// [wikipedia]: https://en.wikipedia.org/wiki/Necklace_(combinatorics)
+pub fn necklace(n: usize, k: usize) -> usize {
+    if n == 0 {
+        return 1;
+    }
+    let mut r = 0;
+    for d in 1 ..= n {
+        if n.is_multiple_of(d) {
+            r += euler_totient(n / d) * k.pow(d as u32);
+        }
+    }
+    r / n
+}
+
+#[test]
+fn necklace_ok() {
+    fn test(n: usize, k: usize, r: usize) {
+        assert_eq!(necklace(n, k), r, "n={n} k={k}");
+    }
+    test(0, 0, 1);
+    test(1, 0, 0);
+    test(0, 2, 1);
+    test(1, 2, 2);
+    test(2, 2, 3);
+    test(3, 2, 4);
+    test(4, 2, 6);
+    test(5, 2, 8);
+    test(6, 2, 14);
+    test(3, 3, 11);
+    test(4, 3, 24);
+}
+
+/// Returns the number of Lyndon words of length `n` over an alphabet of `k` letters.
+///
+/// A Lyndon word is a word strictly smaller than all its other rotations. Equivalently, it is an
+/// aperiodic necklace. The empty word is not a Lyndon word.
+///
+/// See [wikipedia] for more information.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::lyndon;
+/// assert_eq!(lyndon(0, 2), 0);
+/// assert_eq!(lyndon(3, 2), 2);
+/// assert_eq!(lyndon(4, 2), 3);
+/// assert_eq!(lyndon(4, 3), 18);
+/// ```
+///
+/// [wikipedia]: https://en.wikipedia.org/wiki/Lyndon_word
+pub fn lyndon(n: usize, k: usize) -> usize {
+    let (mut p, mut m) = (0, 0);
+    for d in 1 ..= n {
+        if n.is_multiple_of(d) {
+            match moebius(n / d) {
+                1 => p += k.pow(d as u32),
+                -1 => m += k.pow(d as u32),
+                _ => (),
+            }
+        }
+    }
+    (p - m).checked_div(n).unwrap_or(0)
+}
+
+#[test]
+fn lyndon_ok() {
+    fn test(n: usize, k: usize, r: usize) {
+        assert_eq!(lyndon(n, k), r, "n={n} k={k}");
+    }
+    test(0, 2, 0);
+    test(1, 0, 0);
+    test(1, 2, 2);
+    test(2, 2, 1);
+    test(3, 2, 2);
+    test(4, 2, 3);
+    test(5, 2, 6);
+    test(6, 2, 9);
+    test(3, 3, 8);
+    test(4, 3, 18);
+}
+
+/// Returns the number of bracelets of length `n` over an alphabet of `k` letters.
+///
+/// A bracelet is an equivalence class of words under rotation and reversal.
+///
+/// See [wikipedia] for more information.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::bracelet;
+/// assert_eq!(bracelet(0, 2), 1);
+/// assert_eq!(bracelet(4, 2), 6);
+/// assert_eq!(bracelet(6, 2), 13);
+/// assert_eq!(bracelet(4, 3), 21);
+/// ```
+///
+/// [wikipedia]: https://en.wikipedia.org/wiki/Necklace_(combinatorics)#Number_of_bracelets
+pub fn bracelet(n: usize, k: usize) -> usize {
+    if n == 0 {
+        return 1;
+    }
+    let h = k.pow(n as u32 / 2);
+    let s = if n % 2 == 1 { k * h } else { (k + 1) * h / 2 };
+    (necklace(n, k) + s) / 2
+}
+
+#[test]
+fn bracelet_ok() {
+    fn test(n: usize, k: usize, r: usize) {
+        assert_eq!(bracelet(n, k), r, "n={n} k={k}");
+    }
+    test(0, 2, 1);
+    test(1, 0, 0);
+    test(1, 2, 2);
+    test(2, 2, 3);
+    test(3, 2, 4);
+    test(4, 2, 6);
+    test(5, 2, 8);
+    test(6, 2, 13);
+    test(3, 3, 10);
+    test(4, 3, 21);
+}
+
+/// Returns the number of bit sequences of length `n` without `k` consecutive ones.
+///
+/// This is the `k`-bonacci sequence. For `k = 2`, this is the Fibonacci number at index `n + 2`.
+///
+/// See [wikipedia] for more information.
+///
+/// # Panics
+///
+/// Panics if the result overflows. Panics in debug mode if `k == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::run_limited;
+/// assert_eq!(run_limited(0, 2), 1);
+/// assert_eq!(run_limited(3, 1), 1);
+/// assert_eq!(run_limited(3, 2), 5);
+/// assert_eq!(run_limited(4, 2), 8);
+/// assert_eq!(run_limited(4, 3), 13);
+/// assert_eq!(run_limited(4, 5), 16);
+/// ```
+///
+/// [wikipedia]: https://en.wikipedia.org/wiki/Generalizations_of_Fibonacci_numbers
+pub fn run_limited(n: usize, k: usize) -> usize {
+    RunLimited::new(k).get(n).expect("Overflow")
+}
+
+#[test]
+fn run_limited_ok() {
+    fn spec(n: usize, k: usize) -> usize {
+        let mut r = 0;
+        for x in 0 .. 1usize << n {
+            let mut run = 0;
+            let mut valid = true;
+            for i in 0 .. n {
+                run = if x & 1 << i == 0 { 0 } else { run + 1 };
+                valid &= run < k;
+            }
+            r += valid as usize;
+        }
+        r
+    }
+    for n in 0 .. 12 {
+        for k in 1 .. 14 {
+            assert_eq!(run_limited(n, k), spec(n, k), "n={n} k={k}");
+        }
+    }
+    assert_eq!(run_limited(1000, 1), 1);
+    assert_eq!(run_limited(63, 64), 1 << 63);
+}
+
+/// Table of [`run_limited`] for a given `k`.
+///
+/// Values are `None` when they overflow. The table is large enough to hold all values that do not
+/// overflow, except for `k = 1` where all values are one.
+struct RunLimited {
+    k: usize,
+    table: [Option<usize>; RunLimited::LEN],
+}
+
+impl RunLimited {
+    const LEN: usize = 128;
+
+    fn new(k: usize) -> RunLimited {
+        debug_assert!(k > 0, "Failed precondition");
+        let mut table = [None; RunLimited::LEN];
+        // The sum of the (at most) k previous values.
+        let mut sum = Some(0usize);
+        for m in 0 .. RunLimited::LEN {
+            let c = if m < k { sum.and_then(|x| x.checked_add(1)) } else { sum };
+            table[m] = c;
+            sum = sum.zip(c).and_then(|(x, y)| x.checked_add(y));
+            if m >= k {
+                sum = sum.zip(table[m - k]).map(|(x, y)| x - y);
+            }
+        }
+        RunLimited { k, table }
+    }
+
+    fn get(&self, n: usize) -> Option<usize> {
+        match self.table.get(n) {
+            Some(&x) => x,
+            None if self.k == 1 => Some(1),
+            None => None,
+        }
+    }
 }
 
 /// Returns the number of permutations of a multiset.
@@ -213,6 +588,196 @@ fn multinomial_ok() {
     test(&[0, 1, 1, 0, 2, 0], 60);
 }
 
+/// Returns the number of permutations of a multiset.
+///
+/// Returns `None` if the number overflows.
+#[cfg(feature = "alloc")]
+fn checked_multinomial<T: Ord>(xs: &[T]) -> Option<usize> {
+    let mut n = xs.len();
+    let mut r: usize = 1;
+    for i in 0 .. xs.len() {
+        if xs[.. i].contains(&xs[i]) {
+            continue;
+        }
+        let k = xs[i ..].iter().filter(|&x| x == &xs[i]).count();
+        r = r.checked_mul(checked_combination(n, k)?)?;
+        n -= k;
+    }
+    Some(r)
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn checked_multinomial_ok() {
+    for xs in [&[][..], &[0], &[0, 1, 0], &[0, 1, 1, 0, 2, 0], &[0, 0, 1, 1, 2, 2, 3, 3]] {
+        assert_eq!(checked_multinomial(xs), Some(multinomial(xs)), "xs={xs:?}");
+    }
+    let xs: [usize; 21] = core::array::from_fn(|i| i);
+    assert_eq!(checked_multinomial(&xs[.. 20]), Some(2432902008176640000));
+    assert_eq!(checked_multinomial(&xs), None);
+}
+
+/// Memory layout of a batch of objects of the same length.
+///
+/// The batch is seen as a matrix with one row per object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Objects are contiguous: element `j` of object `i` is at `i * len + j`.
+    RowMajor,
+
+    /// Elements of the same position are contiguous: element `j` of object `i` is at
+    /// `j * count + i`.
+    ColumnMajor,
+}
+
+/// Number of elements of the scratch row used by [`write_range`] without allocation.
+const SCRATCH: usize = 64;
+
+/// Writes `count` objects of length `len` to `out` with `layout`.
+///
+/// The first object is written by `init`. The next object is obtained by calling `step` on a copy
+/// of the previous one (in place).
+///
+/// # Panics
+///
+/// Panics without the `alloc` feature if `layout` is [`Layout::ColumnMajor`] and `len > 64`.
+fn write_range<T: Clone>(
+    len: usize,
+    count: usize,
+    layout: Layout,
+    out: &mut [T],
+    init: impl FnOnce(&mut [T]),
+    mut step: impl FnMut(&mut [T]),
+) {
+    debug_assert_eq!(out.len(), len * count, "Failed precondition");
+    if count == 0 || len == 0 {
+        return;
+    }
+    match layout {
+        Layout::RowMajor => {
+            init(&mut out[.. len]);
+            for i in 1 .. count {
+                let (prev, next) = out[(i - 1) * len .. (i + 1) * len].split_at_mut(len);
+                next.clone_from_slice(prev);
+                step(next);
+            }
+        }
+        Layout::ColumnMajor if len <= SCRATCH => {
+            let mut row: [T; SCRATCH] = core::array::from_fn(|_| out[0].clone());
+            write_columns(count, out, &mut row[.. len], init, step);
+        }
+        #[cfg(feature = "alloc")]
+        Layout::ColumnMajor => {
+            let mut row = out[.. len].to_vec();
+            write_columns(count, out, &mut row, init, step);
+        }
+        #[cfg(not(feature = "alloc"))]
+        Layout::ColumnMajor => panic!("Object too long without allocation"),
+    }
+}
+
+/// Writes `count` objects to `out` in column-major order, stepping them in a scratch `row`.
+fn write_columns<T: Clone>(
+    count: usize,
+    out: &mut [T],
+    row: &mut [T],
+    init: impl FnOnce(&mut [T]),
+    mut step: impl FnMut(&mut [T]),
+) {
+    init(row);
+    for i in 0 .. count {
+        if i > 0 {
+            step(row);
+        }
+        for (j, x) in row.iter().enumerate() {
+            out[j * count + i] = x.clone();
+        }
+    }
+}
+
+#[test]
+fn write_range_ok() {
+    fn test(layout: Layout, e: &[usize]) {
+        let mut out = [0; 6];
+        let init = |xs: &mut [usize]| xs.copy_from_slice(&[0, 10]);
+        write_range(2, 3, layout, &mut out, init, |xs| xs.iter_mut().for_each(|x| *x += 1));
+        assert_eq!(out, e, "layout={layout:?}");
+    }
+    test(Layout::RowMajor, &[0, 10, 1, 11, 2, 12]);
+    test(Layout::ColumnMajor, &[0, 1, 2, 10, 11, 12]);
+    // Long objects use an allocated scratch row.
+    let mut out = [0; 2 * 100];
+    let init = |xs: &mut [usize]| xs.iter_mut().enumerate().for_each(|(j, x)| *x = 10 * j);
+    write_range(100, 2, Layout::ColumnMajor, &mut out, init, |xs| xs[0] += 1);
+    assert_eq!(out[.. 4], [0, 1, 10, 10]);
+}
+
+fn euler_totient(mut n: usize) -> usize {
+    let mut r = n;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+            r -= r / p;
+        }
+        p += 1;
+    }
+    if n > 1 {
+        r -= r / n;
+    }
+    r
+}
+
+#[test]
+fn euler_totient_ok() {
+    fn test(n: usize, r: usize) {
+        assert_eq!(euler_totient(n), r, "n={n}");
+    }
+    test(1, 1);
+    test(2, 1);
+    test(3, 2);
+    test(4, 2);
+    test(6, 2);
+    test(9, 6);
+    test(12, 4);
+    test(13, 12);
+}
+
+fn moebius(mut n: usize) -> isize {
+    let mut r = 1;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            r = -r;
+        }
+        p += 1;
+    }
+    if n > 1 {
+        r = -r;
+    }
+    r
+}
+
+#[test]
+fn moebius_ok() {
+    fn test(n: usize, r: isize) {
+        assert_eq!(moebius(n), r, "n={n}");
+    }
+    test(1, 1);
+    test(2, -1);
+    test(3, -1);
+    test(4, 0);
+    test(6, 1);
+    test(12, 0);
+    test(30, -1);
+}
+
 fn is_ordered_set<T: Ord>(xs: &[T]) -> bool {
     xs.windows(2).all(|w| w[0] < w[1])
 }
@@ -16,10 +16,21 @@
 //!
 //! See [wikipedia] for more information.
 //!
+//! This module also provides the cool-lex order (see [`encode_cool`]) in which consecutive
+//! multiset permutations differ by a prefix shift, and its loopless iterator [`CoolIter`].
+//!
 //! [wikipedia]: https://en.wikipedia.org/wiki/Multinomial_theorem#Multinomial_coefficients
 
+#[cfg(feature = "alloc")]
+use alloc::vec;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+use core::borrow::BorrowMut;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use crate::factoradics::{next_permutation, prev_permutation};
+use crate::iter::{Lending, LendingBack, RankedIter};
 
 /// Applies the multiset permutation of the value `p` to the slice `xs`.
 ///
@@ -132,6 +143,71 @@ fn decode_ok() {
     test(&[0, 0, 0, 1, 1, 2], 10, &[0, 0, 2, 1, 0, 1]);
 }
 
+/// Writes the multiset permutations of the values in `range` to `out` with `layout`.
+///
+/// This is faster than calling [`decode_mut`] for each value: only the first value is decoded, the
+/// next permutations are obtained by stepping.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not non-decreasing, `range` is out of range, or
+/// `out.len() != xs.len() * range.len()`. Without the `alloc` feature, panics if `layout` is
+/// [`ColumnMajor`](crate::Layout::ColumnMajor) and `xs.len() > 64`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::multinadics::decode_range_into;
+/// # use number_encoding::Layout;
+/// let mut out = [0; 6];
+/// decode_range_into(&[0, 0, 1], 1 .. 3, Layout::RowMajor, &mut out);
+/// assert_eq!(out, [0, 1, 0, 1, 0, 0]);
+/// decode_range_into(&[0, 0, 1], 1 .. 3, Layout::ColumnMajor, &mut out);
+/// assert_eq!(out, [0, 1, 1, 0, 0, 0]);
+/// ```
+pub fn decode_range_into<T: Clone + Ord>(
+    xs: &[T],
+    range: Range<usize>,
+    layout: crate::Layout,
+    out: &mut [T],
+) {
+    debug_assert!(range.end <= crate::multinomial(xs), "Failed precondition");
+    debug_assert_eq!(out.len(), xs.len() * range.len(), "Failed precondition");
+    let init = |ys: &mut [T]| {
+        ys.clone_from_slice(xs);
+        decode_mut(ys, range.start);
+    };
+    crate::write_range(xs.len(), range.len(), layout, out, init, |xs| {
+        let _ = next_permutation(xs);
+    });
+}
+
+#[test]
+fn decode_range_into_ok() {
+    for layout in [crate::Layout::RowMajor, crate::Layout::ColumnMajor] {
+        for xs in [&[][..], &[0], &[0, 1, 2], &[0, 0, 1, 1], &[0, 1, 1, 2, 3]] {
+            let count = crate::multinomial(xs);
+            let n = xs.len();
+            for start in 0 ..= count {
+                for end in start ..= count {
+                    let len = end - start;
+                    let mut out = xs.repeat(len);
+                    decode_range_into(xs, start .. end, layout, &mut out);
+                    for i in 0 .. len {
+                        let ys: Vec<_> = match layout {
+                            crate::Layout::RowMajor => out[i * n .. (i + 1) * n].to_vec(),
+                            crate::Layout::ColumnMajor => {
+                                (0 .. n).map(|j| out[j * len + i]).collect()
+                            }
+                        };
+                        assert_eq!(ys, decode(xs, start + i), "xs={xs:?} start={start} i={i}");
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Returns the value of a multiset permutation.
 ///
 /// The returned value can be decoded with [`decode`] to get back `xs`.
@@ -231,9 +307,13 @@ fn encode_ok() {
 ///     process(xs);
 /// }
 /// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
 pub struct Iter<'a, T> {
     data: &'a mut [T],
     state: IterState,
+    rank: usize,
 }
 
 enum IterState {
@@ -250,7 +330,65 @@ impl<'a, T: Ord> Iter<'a, T> {
     /// Panics in debug mode if `xs` is not non-decreasing.
     pub fn new(xs: &mut [T]) -> Iter<T> {
         debug_assert!(crate::is_ordered_multiset(xs));
-        Iter { data: xs, state: IterState::New }
+        Iter { data: xs, state: IterState::New, rank: 0 }
+    }
+
+    /// Constructs an iterator starting from a given multiset permutation.
+    ///
+    /// The iteration ends after the last multiset permutation, at which point the slice is
+    /// non-decreasing.
+    pub fn new_from(xs: &mut [T]) -> Iter<'_, T> {
+        let rank = encode(xs);
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Constructs an iterator starting from the multiset permutation of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not non-decreasing or `rank` is out of range.
+    pub fn new_at_rank(xs: &mut [T], rank: usize) -> Iter<'_, T> {
+        decode_mut(xs, rank);
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Constructs an iterator starting from the last multiset permutation with a non-decreasing
+    /// slice.
+    ///
+    /// This is meant for iteration with [`prev`](Iter::prev).
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not non-decreasing or the number of multiset permutations
+    /// overflows.
+    pub fn new_rev(xs: &mut [T]) -> Iter<'_, T> {
+        debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+        let rank = crate::multinomial(xs) - 1;
+        xs.reverse();
+        Iter { data: xs, state: IterState::New, rank }
+    }
+
+    /// Returns the value of the current multiset permutation.
+    ///
+    /// The current multiset permutation is the one last returned by [`next`](Iter::next), or the
+    /// one it will return if it was not called yet. After the end of the iteration, this is the
+    /// number of multiset permutations.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the multiset permutation of a given value.
+    ///
+    /// The next call to [`next`](Iter::next) returns this multiset permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        self.data.sort_unstable();
+        decode_mut(self.data, rank);
+        self.state = IterState::New;
+        self.rank = rank;
     }
 
     /// Returns the next permutation.
@@ -261,7 +399,8 @@ impl<'a, T: Ord> Iter<'a, T> {
         match self.state {
             IterState::New => self.state = IterState::Running,
             IterState::Running => {
-                if self.advance() {
+                self.rank += 1;
+                if !next_permutation(self.data) {
                     self.state = IterState::Done;
                 }
             }
@@ -274,23 +413,53 @@ impl<'a, T: Ord> Iter<'a, T> {
         }
     }
 
-    fn advance(&mut self) -> bool {
-        let n = self.data.len();
-        if n == 0 {
-            return true;
-        }
-        let mut i = n - 1;
-        while i > 0 && self.data[i - 1] >= self.data[i] {
-            i -= 1;
+    /// Returns the previous multiset permutation.
+    ///
+    /// This is the reverse of [`next`](Iter::next): the first call returns the current multiset
+    /// permutation and the iteration ends after the first multiset permutation. If iteration is
+    /// over, returns `None` and the slice is restored to its first value.
+    pub fn prev(&mut self) -> Option<&[T]> {
+        match self.state {
+            IterState::New => self.state = IterState::Running,
+            IterState::Running => {
+                if prev_permutation(self.data) {
+                    self.rank -= 1;
+                } else {
+                    self.data.reverse();
+                    self.state = IterState::Done;
+                }
+            }
+            IterState::Done => (),
         }
-        if i == 0 {
-            self.data.reverse();
-            return true;
+        match self.state {
+            IterState::New => unreachable!(),
+            IterState::Running => Some(self.data),
+            IterState::Done => None,
         }
-        self.data[i ..].reverse();
-        let k = self.data[i ..].iter().position(|x| x > &self.data[i - 1]).unwrap();
-        self.data.swap(i - 1, i + k);
-        false
+    }
+}
+
+impl<T: Ord> Lending for Iter<'_, T> {
+    type Item = T;
+
+    fn lend(&mut self) -> Option<&[T]> {
+        self.next()
+    }
+}
+
+impl<T: Ord> LendingBack for Iter<'_, T> {
+    fn lend_back(&mut self) -> Option<&[T]> {
+        self.prev()
+    }
+}
+
+impl<T: Ord> RankedIter for Iter<'_, T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
     }
 }
 
@@ -322,3 +491,531 @@ fn iter_ok() {
         &[1, 1, 0, 0],
     ]);
 }
+
+#[test]
+fn iter_rank_ok() {
+    let mut xs = [0, 0, 1, 1];
+    let mut iter = Iter::new_at_rank(&mut xs, 2);
+    for i in 2 .. 6 {
+        assert_eq!(encode(iter.next().unwrap()), i);
+        assert_eq!(iter.rank(), i);
+    }
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.rank(), 6);
+    iter.seek(3);
+    assert_eq!(iter.next(), Some(&[1, 0, 0, 1][..]));
+    let mut xs = [1, 0, 1, 0];
+    let mut iter = Iter::new_from(&mut xs);
+    assert_eq!(iter.rank(), 4);
+    assert_eq!(iter.next(), Some(&[1, 0, 1, 0][..]));
+    assert_eq!(iter.next(), Some(&[1, 1, 0, 0][..]));
+    assert_eq!(iter.next(), None);
+    assert_eq!(xs, [0, 0, 1, 1]);
+}
+
+/// Returns the multiplicities of the distinct elements of a non-decreasing slice.
+fn multiplicities<T: Ord>(xs: &[T]) -> impl Iterator<Item = usize> + '_ {
+    xs.chunk_by(|x, y| x == y).map(<[T]>::len)
+}
+
+// The cool-lex order of a multiset E with smallest element m is the cool-lex order of E - m
+// followed by m, in which for each other element a, the cool-lex order of E - a (rotated by one)
+// followed by a is inserted after the permutation [nonincreasing(E - a - m), a, m]. The helpers
+// below work on the multiplicities `c` of the distinct elements, with `len` the number of
+// elements and `count` the number of multiset permutations.
+
+/// Returns the cool-lex value of the permutation `[nonincreasing(E - a), a]` of E.
+///
+/// The multiplicities `c` are those of the elements smaller than `a`, and `w` is the number of
+/// elements smaller than or equal to `a`.
+fn cool_anchor(
+    c: impl Iterator<Item = usize>,
+    mut w: usize,
+    mut len: usize,
+    mut count: u128,
+) -> usize {
+    // The permutation follows the smallest element of E until it is a.
+    let mut r = 0;
+    for cs in c {
+        w -= cs;
+        for cs in (1 ..= cs).rev() {
+            r += count * w as u128 / len as u128;
+            count = count * cs as u128 / len as u128;
+            len -= 1;
+        }
+    }
+    r as usize
+}
+
+/// Returns the cool-lex value of a permutation of E ending with `z` from the value `r` of its
+/// prefix (in E - z).
+#[cfg(feature = "alloc")]
+fn cool_rank(c: &mut [usize], len: usize, count: u128, z: usize, r: usize) -> usize {
+    if count == 1 {
+        return 0;
+    }
+    let m = c.iter().position(|&x| x > 0).unwrap();
+    let sub = count * c[m] as u128 / len as u128;
+    c[m] -= 1;
+    let mut skipped = 0;
+    for a in m + 1 .. c.len() {
+        if c[a] == 0 || (z != m && a > z) {
+            continue;
+        }
+        let size = (count * c[a] as u128 / len as u128) as usize;
+        let w = c[..= a].iter().sum();
+        let anchor = cool_anchor(c[.. a].iter().copied(), w, len - 1, sub);
+        if a == z {
+            c[m] += 1;
+            return anchor + 1 + skipped + (r + size - 1) % size;
+        }
+        if z != m || anchor < r {
+            skipped += size;
+        }
+    }
+    c[m] += 1;
+    r + skipped
+}
+
+/// Applies the multiset permutation of the value `p` in cool-lex order to the slice `xs`.
+///
+/// The written permutation can be encoded with [`encode_cool`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::multinadics::{decode_cool_mut, encode_cool};
+/// # let mut xs = [0, 0, 0, 1, 1, 2];
+/// # let p = 15;
+/// decode_cool_mut(&mut xs, p);
+/// assert_eq!(encode_cool(&xs), p);
+/// ```
+///
+/// See [`decode_cool`] for a version that allocates a vector for the permutation.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not non-decreasing or `p` is out of range.
+pub fn decode_cool_mut<T: Ord>(xs: &mut [T], mut p: usize) {
+    debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+    let mut count = crate::multinomial(xs) as u128;
+    debug_assert!((p as u128) < count, "Failed precondition");
+    // The elements are chosen from the last one, and the remaining ones stay non-decreasing.
+    for len in (1 ..= xs.len()).rev() {
+        let ys = &mut xs[.. len];
+        let run = |i: usize| ys[i ..].iter().take_while(|&y| y == &ys[i]).count();
+        // The chosen element is the last one of the run starting at z.
+        let mut z = 0;
+        if count > 1 {
+            let sub = count * run(0) as u128 / len as u128;
+            let mut skipped = 0;
+            let mut a = run(0);
+            while a < len {
+                let size = (count * run(a) as u128 / len as u128) as usize;
+                let anchor = cool_anchor(multiplicities(&ys[1 .. a]), a - 1 + run(a), len - 1, sub);
+                let start = anchor + 1 + skipped;
+                if p < start {
+                    break;
+                }
+                if p < start + size {
+                    z = a;
+                    p = (p - start + 1) % size;
+                    break;
+                }
+                skipped += size;
+                a += run(a);
+            }
+            if z == 0 {
+                p -= skipped;
+            }
+        }
+        let c = run(z);
+        count = count * c as u128 / len as u128;
+        ys[z + c - 1 ..].rotate_left(1);
+    }
+}
+
+/// Returns the multiset permutation of the value `p` in cool-lex order to the slice `xs`.
+///
+/// The returned permutation can be encoded with [`encode_cool`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::multinadics::{decode_cool, encode_cool};
+/// # let xs = [0, 0, 0, 1, 1, 2];
+/// # let p = 15;
+/// let xs = decode_cool(&xs, p);
+/// assert_eq!(encode_cool(&xs), p);
+/// ```
+///
+/// See [`decode_cool_mut`] for a version that applies the permutation to the slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not non-decreasing or `p` is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::multinadics::decode_cool;
+/// assert_eq!(decode_cool(&[0, 0, 1], 0), &[1, 0, 0]);
+/// assert_eq!(decode_cool(&[0, 0, 1], 1), &[0, 1, 0]);
+/// assert_eq!(decode_cool(&[0, 0, 1], 2), &[0, 0, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_cool<T: Clone + Ord>(xs: &[T], p: usize) -> Vec<T> {
+    let mut xs = xs.to_vec();
+    decode_cool_mut(&mut xs[..], p);
+    xs
+}
+
+#[test]
+fn decode_cool_ok() {
+    fn test(xs: &[usize], r: &[&[usize]]) {
+        for (p, &r) in r.iter().enumerate() {
+            assert_eq!(decode_cool(xs, p), r, "xs={xs:?} p={p}");
+        }
+    }
+    test(&[], &[&[]]);
+    test(&[0, 0], &[&[0, 0]]);
+    test(&[0, 1, 2], &[&[2, 1, 0], &[0, 2, 1], &[2, 0, 1], &[1, 2, 0], &[0, 1, 2], &[1, 0, 2]]);
+    test(
+        &[0, 0, 1, 1],
+        &[&[1, 1, 0, 0], &[0, 1, 1, 0], &[1, 0, 1, 0], &[0, 1, 0, 1], &[0, 0, 1, 1], &[1, 0, 0, 1]],
+    );
+    test(
+        &[0, 0, 1, 2],
+        &[
+            &[2, 1, 0, 0],
+            &[0, 2, 1, 0],
+            &[2, 0, 1, 0],
+            &[0, 2, 0, 1],
+            &[0, 0, 2, 1],
+            &[2, 0, 0, 1],
+            &[1, 2, 0, 0],
+            &[0, 1, 2, 0],
+            &[1, 0, 2, 0],
+            &[0, 1, 0, 2],
+            &[0, 0, 1, 2],
+            &[1, 0, 0, 2],
+        ],
+    );
+}
+
+/// Returns the value of a multiset permutation in cool-lex order.
+///
+/// The returned value can be decoded with [`decode_cool`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::multinadics::{decode_cool, encode_cool};
+/// # let xs = &[0, 1, 1, 0, 2, 0];
+/// let mut ys = xs.to_vec();
+/// ys.sort();
+/// let p = encode_cool(xs);
+/// assert_eq!(decode_cool(&ys, p), xs);
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::multinadics::encode_cool;
+/// assert_eq!(encode_cool(&[1, 0, 0]), 0);
+/// assert_eq!(encode_cool(&[0, 1, 0]), 1);
+/// assert_eq!(encode_cool(&[0, 0, 1]), 2);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_cool<T: Ord>(xs: &[T]) -> usize {
+    let mut values: Vec<&T> = xs.iter().collect();
+    values.sort();
+    values.dedup();
+    let mut c = vec![0; values.len()];
+    let mut count = 1;
+    let mut r = 0;
+    for (i, x) in xs.iter().enumerate() {
+        let z = values.binary_search(&x).unwrap();
+        c[z] += 1;
+        count = count * (i + 1) as u128 / c[z] as u128;
+        r = cool_rank(&mut c, i + 1, count, z, r);
+    }
+    r
+}
+
+#[test]
+fn encode_cool_ok() {
+    for xs in
+        [&[][..], &[0], &[0, 1], &[0, 0, 1, 1, 2], &[0, 1, 2, 3, 4], &[0, 0, 0, 1, 1, 1, 2, 2]]
+    {
+        for p in 0 .. crate::multinomial(xs) {
+            assert_eq!(encode_cool(&decode_cool(xs, p)), p, "xs={xs:?} p={p}");
+        }
+    }
+}
+
+/// Iterates over all permutations of a multiset in cool-lex order.
+///
+/// Consecutive permutations differ by a prefix shift: the element at some index `l - 1` moves to
+/// the front. Advancing takes constant time (the iteration is loopless, the permutation being
+/// stored as a linked list) and reports the length `l` of the shifted prefix:
+///
+/// ```rust
+/// # use number_encoding::multinadics::{CoolIter, encode_cool};
+/// # let xs = [0, 0, 1, 1, 2];
+/// let mut iter = CoolIter::new(&xs);
+/// let mut ys: Vec<_> = iter.iter().cloned().collect();
+/// while let Some(l) = iter.advance() {
+///     ys[.. l].rotate_right(1);
+///     assert!(iter.iter().eq(&ys));
+///     assert_eq!(encode_cool(&ys), iter.rank());
+/// }
+/// assert_eq!(iter.rank(), 29);
+/// ```
+///
+/// # Examples
+///
+/// Binary strings of fixed weight:
+///
+/// ```rust
+/// # use number_encoding::multinadics::CoolIter;
+/// let mut iter = CoolIter::new(&[0, 0, 1, 1]);
+/// assert!(iter.iter().eq(&[1, 1, 0, 0]));
+/// assert_eq!(iter.advance(), Some(4));
+/// assert!(iter.iter().eq(&[0, 1, 1, 0]));
+/// assert_eq!(iter.advance(), Some(2));
+/// assert!(iter.iter().eq(&[1, 0, 1, 0]));
+/// ```
+///
+/// In a no-std environment, you can pass buffers for the elements and the linked list:
+///
+/// ```rust
+/// # use number_encoding::multinadics::CoolIter;
+/// let mut xs = [0, 0, 1, 1, 2];
+/// let mut next = [0; 5];
+/// let mut iter = CoolIter::new_with_buffer(&mut xs[..], &mut next[..]);
+/// assert!(iter.iter().eq(&[2, 1, 1, 0, 0]));
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct CoolIter<T, V: BorrowMut<[T]>, L: BorrowMut<[usize]>> {
+    values: V,
+    next: L,
+    head: usize,
+    // The last node of the longest non-increasing prefix (but not the last node), and its index.
+    last: usize,
+    index: usize,
+    lent: bool,
+    rank: usize,
+    element: PhantomData<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone + Ord> CoolIter<T, Vec<T>, Vec<usize>> {
+    /// Constructs an iterator with a non-decreasing slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not non-decreasing.
+    pub fn new(xs: &[T]) -> CoolIter<T, Vec<T>, Vec<usize>> {
+        CoolIter::new_with_buffer(xs.to_vec(), vec![0; xs.len()])
+    }
+
+    /// Constructs an iterator starting from the permutation of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not non-decreasing or `rank` is out of range.
+    pub fn new_at_rank(xs: &[T], rank: usize) -> CoolIter<T, Vec<T>, Vec<usize>> {
+        let mut iter = CoolIter::new(xs);
+        iter.seek(rank);
+        iter
+    }
+}
+
+impl<T: Ord, V: BorrowMut<[T]>, L: BorrowMut<[usize]>> CoolIter<T, V, L> {
+    /// Constructs an iterator with a non-decreasing buffer and a buffer for the linked list.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `values` is not non-decreasing or `next` does not have the same
+    /// length as `values`.
+    pub fn new_with_buffer(mut values: V, next: L) -> CoolIter<T, V, L> {
+        debug_assert!(crate::is_ordered_multiset(values.borrow()), "Failed precondition");
+        debug_assert_eq!(next.borrow().len(), values.borrow().len(), "Failed precondition");
+        values.borrow_mut().reverse();
+        let mut iter = CoolIter {
+            values,
+            next,
+            head: 0,
+            last: 0,
+            index: 0,
+            lent: false,
+            rank: 0,
+            element: PhantomData,
+        };
+        iter.reset();
+        iter
+    }
+
+    /// Links the values in order.
+    fn reset(&mut self) {
+        let v = self.values.borrow();
+        let n = v.len();
+        for (i, x) in self.next.borrow_mut().iter_mut().enumerate() {
+            *x = i + 1;
+        }
+        self.head = 0;
+        let k = v.windows(2).take_while(|w| w[0] >= w[1]).count() + 1;
+        self.index = k.min(n.max(1) - 1).saturating_sub(1);
+        self.last = self.index;
+    }
+
+    /// Moves the values to the order of the linked list, then links them in order.
+    fn relink(&mut self) {
+        let (v, next) = (self.values.borrow_mut(), self.next.borrow_mut());
+        // Replace the links with the positions of the nodes, then move each value to its position.
+        let mut node = self.head;
+        for i in 0 .. v.len() {
+            let succ = next[node];
+            next[node] = i;
+            node = succ;
+        }
+        for i in 0 .. v.len() {
+            while next[i] != i {
+                let j = next[i];
+                v.swap(i, j);
+                next.swap(i, j);
+            }
+        }
+        self.reset();
+    }
+
+    /// Returns the elements of the current permutation.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        let (v, next) = (self.values.borrow(), self.next.borrow());
+        let mut node = self.head;
+        core::iter::from_fn(move || {
+            let x = v.get(node)?;
+            node = next[node];
+            Some(x)
+        })
+    }
+
+    /// Returns the value of the current permutation.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the permutation of a given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        let v = self.values.borrow_mut();
+        v.sort_unstable();
+        decode_cool_mut(v, rank);
+        self.reset();
+        self.lent = false;
+        self.rank = rank;
+    }
+
+    /// Advances to the next permutation.
+    ///
+    /// Returns the length `l` of the shifted prefix (the element at index `l - 1` moved to the
+    /// front), or `None` if the current permutation is the last one (in which case it is not
+    /// modified).
+    pub fn advance(&mut self) -> Option<usize> {
+        // This is the algorithm of Williams (Loopless generation of multiset permutations using a
+        // constant number of variables by prefix shifts), which also tracks the index of `last`.
+        let (v, next) = (self.values.borrow(), self.next.borrow_mut());
+        let n = v.len();
+        if n < 2 {
+            return None;
+        }
+        let i = self.last;
+        let j = next[i];
+        if next[j] == n && v[j] >= v[self.head] {
+            return None;
+        }
+        let (s, l) = if next[j] < n && v[i] >= v[next[j]] {
+            (j, self.index + 3)
+        } else {
+            (i, self.index + 2)
+        };
+        let t = next[s];
+        next[s] = next[t];
+        next[t] = self.head;
+        if v[t] < v[self.head] {
+            self.last = t;
+            self.index = 0;
+        } else {
+            self.index += 1;
+        }
+        self.head = t;
+        self.rank += 1;
+        Some(l)
+    }
+}
+
+/// Lends the current permutation, then advances before each following call.
+///
+/// Lending moves the values to the order of the permutation, which takes linear time.
+impl<T: Ord, V: BorrowMut<[T]>, L: BorrowMut<[usize]>> Lending for CoolIter<T, V, L> {
+    type Item = T;
+
+    fn lend(&mut self) -> Option<&[T]> {
+        if self.lent && self.advance().is_none() {
+            return None;
+        }
+        self.lent = true;
+        self.relink();
+        Some(self.values.borrow())
+    }
+}
+
+impl<T: Ord, V: BorrowMut<[T]>, L: BorrowMut<[usize]>> RankedIter for CoolIter<T, V, L> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
+    }
+}
+
+#[test]
+fn cool_iter_ok() {
+    for xs in
+        [&[][..], &[0], &[0, 0], &[0, 1], &[0, 0, 1, 1, 2], &[0, 1, 2, 3], &[0, 0, 0, 1, 1, 1, 2]]
+    {
+        let count = crate::multinomial(xs);
+        let mut iter = CoolIter::new(xs);
+        let mut ys: Vec<_> = iter.iter().cloned().collect();
+        for p in 0 .. count {
+            assert_eq!(iter.rank(), p, "xs={xs:?}");
+            assert_eq!(ys, decode_cool(xs, p), "xs={xs:?} p={p}");
+            match iter.advance() {
+                Some(l) => ys[.. l].rotate_right(1),
+                None => assert_eq!(p + 1, count, "xs={xs:?}"),
+            }
+            assert!(iter.iter().eq(&ys), "xs={xs:?} p={p}");
+        }
+        for p in 0 .. count {
+            let r: Vec<_> = CoolIter::new_at_rank(xs, p).vecs().ranked().collect();
+            assert_eq!(r.len(), count - p, "xs={xs:?} p={p}");
+            for (q, ys) in r {
+                assert_eq!(ys, decode_cool(xs, q), "xs={xs:?} p={p} q={q}");
+            }
+        }
+    }
+    let mut xs = [0, 0, 1, 2];
+    let mut next = [0; 4];
+    let mut iter = CoolIter::new_with_buffer(&mut xs[..], &mut next[..]);
+    let mut p = 0;
+    while let Some(ys) = iter.lend() {
+        assert_eq!(ys, decode_cool(&[0, 0, 1, 2], p), "p={p}");
+        p += 1;
+    }
+    assert_eq!(p, 12);
+    iter.seek(5);
+    assert_eq!(iter.lend(), Some(&decode_cool(&[0, 0, 1, 2], 5)[..]));
+    assert!(iter.advance().is_some());
+    assert!(iter.iter().eq(&decode_cool(&[0, 0, 1, 2], 6)));
+}
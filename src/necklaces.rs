@@ -0,0 +1,1256 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Necklace number system
+//!
+//! Words of length `n` over an alphabet of `k` letters are represented as slices of values less
+//! than `k`. A necklace is represented by its smallest rotation, a bracelet by its smallest
+//! rotation or reversed rotation. Necklaces, Lyndon words, and bracelets are ranked in value order
+//! (i.e. lexicographic order of their representative).
+//!
+//! Ranking and unranking necklaces, Lyndon words, and bracelets takes polynomial time.
+//!
+//! This module also ranks circular arrangements of distinct elements, up to rotation (see
+//! [`encode_circular`]) and up to rotation and reversal (see [`encode_circular_free`]).
+//!
+//! See [wikipedia] for more information.
+//!
+//! [wikipedia]: https://en.wikipedia.org/wiki/Necklace_(combinatorics)
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::BorrowMut;
+
+use crate::iter::{Lending, RankedIter};
+
+fn rotation<T>(xs: &[T], i: usize) -> impl Iterator<Item = &T> {
+    xs[i ..].iter().chain(&xs[.. i])
+}
+
+fn reversed_rotation<T>(xs: &[T], i: usize) -> impl Iterator<Item = &T> {
+    let n = xs.len();
+    xs[.. n - i].iter().rev().chain(xs[n - i ..].iter().rev())
+}
+
+/// Returns whether a word is the representative of its necklace.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::is_necklace;
+/// assert!(is_necklace(&[0, 0, 1]));
+/// assert!(is_necklace(&[0, 1, 0, 1]));
+/// assert!(!is_necklace(&[0, 1, 0]));
+/// ```
+pub fn is_necklace<T: Ord>(xs: &[T]) -> bool {
+    (1 .. xs.len()).all(|i| rotation(xs, i).ge(xs))
+}
+
+#[test]
+fn is_necklace_ok() {
+    fn test(xs: &[usize], r: bool) {
+        assert_eq!(is_necklace(xs), r, "xs={xs:?}");
+    }
+    test(&[], true);
+    test(&[1], true);
+    test(&[0, 0], true);
+    test(&[0, 1], true);
+    test(&[1, 0], false);
+    test(&[0, 1, 0, 1], true);
+    test(&[0, 1, 1, 0], false);
+    test(&[0, 0, 1, 0, 1], true);
+    test(&[0, 1, 0, 0, 1], false);
+}
+
+/// Returns whether a word is a Lyndon word.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::is_lyndon;
+/// assert!(is_lyndon(&[0, 0, 1]));
+/// assert!(!is_lyndon(&[0, 1, 0, 1]));
+/// assert!(!is_lyndon(&[0, 1, 0]));
+/// ```
+pub fn is_lyndon<T: Ord>(xs: &[T]) -> bool {
+    !xs.is_empty() && (1 .. xs.len()).all(|i| rotation(xs, i).gt(xs))
+}
+
+#[test]
+fn is_lyndon_ok() {
+    fn test(xs: &[usize], r: bool) {
+        assert_eq!(is_lyndon(xs), r, "xs={xs:?}");
+    }
+    test(&[], false);
+    test(&[1], true);
+    test(&[0, 0], false);
+    test(&[0, 1], true);
+    test(&[1, 0], false);
+    test(&[0, 1, 0, 1], false);
+    test(&[0, 0, 1, 0, 1], true);
+}
+
+/// Returns whether a word is the representative of its bracelet.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::is_bracelet;
+/// assert!(is_bracelet(&[0, 0, 1, 0, 2]));
+/// assert!(!is_bracelet(&[0, 0, 2, 0, 1]));
+/// ```
+pub fn is_bracelet<T: Ord>(xs: &[T]) -> bool {
+    is_necklace(xs) && (0 .. xs.len()).all(|i| reversed_rotation(xs, i).ge(xs))
+}
+
+#[test]
+fn is_bracelet_ok() {
+    fn test(xs: &[usize], r: bool) {
+        assert_eq!(is_bracelet(xs), r, "xs={xs:?}");
+    }
+    test(&[], true);
+    test(&[0, 1], true);
+    test(&[1, 0], false);
+    test(&[0, 0, 1, 1], true);
+    test(&[0, 0, 1, 0, 2], true);
+    test(&[0, 0, 2, 0, 1], false);
+    test(&[0, 1, 2], true);
+    test(&[0, 2, 1], false);
+}
+
+fn smallest_rotation<T: Ord>(xs: &[T]) -> usize {
+    let mut r = 0;
+    for i in 1 .. xs.len() {
+        if rotation(xs, i).lt(rotation(xs, r)) {
+            r = i;
+        }
+    }
+    r
+}
+
+/// Rotates a word to the representative of its necklace.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::canonicalize;
+/// let mut xs = [1, 0, 2, 0];
+/// canonicalize(&mut xs);
+/// assert_eq!(xs, [0, 1, 0, 2]);
+/// ```
+pub fn canonicalize<T: Ord>(xs: &mut [T]) {
+    let i = smallest_rotation(xs);
+    xs.rotate_left(i);
+}
+
+#[test]
+fn canonicalize_ok() {
+    fn test(xs: &[usize], r: &[usize]) {
+        let mut ys = xs.to_vec();
+        canonicalize(&mut ys);
+        assert_eq!(ys, r, "xs={xs:?}");
+    }
+    test(&[], &[]);
+    test(&[1, 0], &[0, 1]);
+    test(&[1, 0, 1, 0], &[0, 1, 0, 1]);
+    test(&[0, 2, 0, 1], &[0, 1, 0, 2]);
+    test(&[2, 0, 0, 1, 0], &[0, 0, 1, 0, 2]);
+}
+
+/// Rotates and possibly reverses a word to the representative of its bracelet.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::canonicalize_bracelet;
+/// let mut xs = [0, 2, 1];
+/// canonicalize_bracelet(&mut xs);
+/// assert_eq!(xs, [0, 1, 2]);
+/// ```
+pub fn canonicalize_bracelet<T: Ord>(xs: &mut [T]) {
+    canonicalize(xs);
+    let n = xs.len();
+    let mut r = 0;
+    for i in 1 .. n {
+        if reversed_rotation(xs, i).lt(reversed_rotation(xs, r)) {
+            r = i;
+        }
+    }
+    if reversed_rotation(xs, r).lt(xs.iter()) {
+        xs.reverse();
+        xs.rotate_left(r);
+    }
+}
+
+#[test]
+fn canonicalize_bracelet_ok() {
+    fn test(xs: &[usize], r: &[usize]) {
+        let mut ys = xs.to_vec();
+        canonicalize_bracelet(&mut ys);
+        assert_eq!(ys, r, "xs={xs:?}");
+    }
+    test(&[], &[]);
+    test(&[1, 0], &[0, 1]);
+    test(&[0, 2, 1], &[0, 1, 2]);
+    test(&[2, 0, 0, 1, 0], &[0, 0, 1, 0, 2]);
+    test(&[1, 0, 0, 2, 0], &[0, 0, 1, 0, 2]);
+    test(&[1, 1, 0, 2, 0, 0], &[0, 0, 1, 1, 0, 2]);
+}
+
+/// Maximum number of states of the automaton.
+///
+/// This bounds the length of ranked words to 63 letters, which is also the limit of
+/// [`necklace`](crate::necklace) for at least 2 letters.
+const STATES: usize = 64;
+
+/// Automaton recognizing words whose rotations are all greater than or equal to a pattern.
+///
+/// The states are the lengths of the longest suffix of the input that is a prefix of the pattern.
+/// From a state `s`, reading a letter smaller than `least[s]` fails, reading `least[s]` moves to
+/// `next[s]`, and reading a larger letter moves to state 0.
+struct Automaton {
+    m: usize,
+    k: usize,
+    least: [usize; STATES],
+    next: [usize; STATES],
+}
+
+impl Automaton {
+    fn new(t: &[usize], k: usize) -> Automaton {
+        let n = t.len();
+        assert!(n < STATES, "Word too long");
+        let mut border = [0; STATES];
+        for i in 1 .. n {
+            let mut j = border[i];
+            while j > 0 && t[i] != t[j] {
+                j = border[j];
+            }
+            if t[i] == t[j] {
+                j += 1;
+            }
+            border[i + 1] = j;
+        }
+        // A letter is read from the longest border of the input it extends, and fails if it is
+        // smaller than the pattern letter after any of them.
+        let mut least = [0; STATES];
+        let mut next = [0; STATES];
+        for s in 0 ..= n {
+            let b = border[s];
+            match t.get(s) {
+                Some(&x) if s == 0 || least[b] <= x => (least[s], next[s]) = (x, s + 1),
+                _ => (least[s], next[s]) = (least[b], next[b]),
+            }
+        }
+        Automaton { m: n + 1, k, least, next }
+    }
+
+    /// Returns the number of letters moving a state to state 0.
+    fn others(&self, s: usize) -> usize {
+        self.k - 1 - self.least[s]
+    }
+
+    /// Returns the state reached by reading a letter, unless it fails.
+    fn read(&self, s: usize, c: usize) -> Option<usize> {
+        match c.cmp(&self.least[s]) {
+            core::cmp::Ordering::Less => None,
+            core::cmp::Ordering::Equal => Some(self.next[s]),
+            core::cmp::Ordering::Greater => Some(0),
+        }
+    }
+
+    /// Returns the states reached by reading the same letter from a forward state `f` and a
+    /// backward state `v`.
+    ///
+    /// The first two values are the states reached by reading the smallest letter that does not
+    /// fail. The third value is the number of larger letters, which move both states to state 0.
+    fn read_pair(&self, f: usize, v: usize) -> (usize, usize, usize) {
+        let c = self.least[f].max(self.least[v]);
+        let g = if self.least[f] == c { self.next[f] } else { 0 };
+        let b = if self.least[v] == c { self.next[v] } else { 0 };
+        (g, b, self.k - 1 - c)
+    }
+
+    /// Returns the number of words of length `d` that loop on some state.
+    ///
+    /// Those are the words with period dividing `d` whose rotations are all greater than or equal
+    /// to the pattern. The state on which a word loops is unique.
+    fn count_above(&self, d: usize) -> usize {
+        let m = self.m;
+        // Walks from state 0 to state 0 of each length.
+        let mut walks = [0; STATES];
+        let mut cur = [0; STATES];
+        let mut nxt = [0; STATES];
+        walks[0] = 1;
+        cur[0] = 1;
+        for walk in &mut walks[1 ..= d] {
+            nxt.fill(0);
+            for s in 0 .. m {
+                nxt[self.next[s]] += cur[s];
+                nxt[0] += self.others(s) * cur[s];
+            }
+            core::mem::swap(&mut cur, &mut nxt);
+            *walk = cur[0];
+        }
+        // A word going through state 0 is counted by its rotation starting after its last visit,
+        // since the rotations starting on the path before the first visit give back the word.
+        let mut r = 0;
+        let mut s = 0;
+        for i in 1 ..= d {
+            r += i * self.others(s) * walks[d - i];
+            s = self.next[s];
+        }
+        // The other words are determined by the state on which they loop.
+        for s in 0 .. m {
+            r += ((0 .. d).fold(s, |x, _| self.next[x]) == s) as usize;
+        }
+        r
+    }
+
+    /// Reads a letter with pairs of a forward state and a backward state.
+    ///
+    /// The forward state is updated by reading the letter. The backward state is replaced by the
+    /// states reading the letter into it. Pairs are indexed by `forward * m + backward` where `m`
+    /// is the number of states.
+    fn step_pairs(&self, cur: &[usize], nxt: &mut [usize]) {
+        let m = self.m;
+        nxt.fill(0);
+        for f in 0 .. m {
+            for v in 0 .. m {
+                let (g, b, w) = self.read_pair(f, v);
+                nxt[g * m + v] += cur[f * m + b];
+                nxt[v] += w * cur[f * m];
+            }
+        }
+    }
+
+    /// Returns the sum over the rotations of the number of words they fix whose rotations and
+    /// reversed rotations are all greater than or equal to the pattern.
+    ///
+    /// A word fixed by a rotation is a closed walk of its period and of its reverse, which has a
+    /// unique pair of states. As in [`count_above`](Automaton::count_above), the words going
+    /// through forward state 0 are counted by their rotation starting after their last visit.
+    fn count_rotations(&self, x: &mut [usize], y: &mut [usize]) -> usize {
+        let m = self.m;
+        let n = m - 1;
+        let mut r = 0;
+        for b0 in 0 .. m {
+            // Backward states of the pairs before the first visit of forward state 0.
+            let mut path = [0; STATES];
+            let mut path_nxt = [0; STATES];
+            path[b0] = 1;
+            let (mut cur, mut nxt) = (&mut x[.. m * m], &mut y[.. m * m]);
+            cur.fill(0);
+            let mut f = 0;
+            for i in 1 ..= n {
+                self.step_pairs(cur, nxt);
+                path_nxt.fill(0);
+                for v in 0 .. m {
+                    let (g, b, w) = self.read_pair(f, v);
+                    if g == 0 {
+                        nxt[v] += i * path[b];
+                    } else {
+                        path_nxt[v] += path[b];
+                    }
+                    nxt[v] += i * w * path[0];
+                }
+                f = self.next[f];
+                core::mem::swap(&mut cur, &mut nxt);
+                core::mem::swap(&mut path, &mut path_nxt);
+                if n.is_multiple_of(i) {
+                    r += crate::euler_totient(n / i) * cur[b0];
+                }
+            }
+        }
+        // The other words are determined by their forward state.
+        let mut word = [0; STATES];
+        for s in 0 .. m {
+            let mut x = s;
+            for d in 1 ..= n {
+                word[d - 1] = self.least[x];
+                x = self.next[x];
+                let closed =
+                    |b| word[.. d].iter().rev().try_fold(b, |b, &c| self.read(b, c)) == Some(b);
+                if x == s && n.is_multiple_of(d) && (0 .. m).any(closed) {
+                    r += crate::euler_totient(n / d);
+                }
+            }
+        }
+        r
+    }
+
+    /// Returns the number of words `a u b rev(u)` whose rotations are all greater than or equal to
+    /// the pattern, where `u` has length `h` and the letters `a` and `b` are only present if
+    /// `outer` and `middle` respectively.
+    ///
+    /// The walk of the automaton is followed from both ends of `u` at once: forward from the start
+    /// and backward from the end (since `rev(u)` is read in reverse).
+    fn count_symmetric(
+        &self,
+        x: &mut [usize],
+        y: &mut [usize],
+        h: usize,
+        outer: bool,
+        middle: bool,
+    ) -> usize {
+        let m = self.m;
+        let (mut cur, mut nxt) = (&mut x[.. m * m], &mut y[.. m * m]);
+        cur.fill(0);
+        for s in 0 .. m {
+            if outer {
+                cur[self.next[s] * m + s] += 1;
+                cur[s] += self.others(s);
+            } else {
+                cur[s * m + s] += 1;
+            }
+        }
+        for _ in 0 .. h {
+            self.step_pairs(cur, nxt);
+            core::mem::swap(&mut cur, &mut nxt);
+        }
+        let mut r = 0;
+        for f in 0 .. m {
+            for b in 0 .. m {
+                let x = cur[f * m + b];
+                if !middle {
+                    r += if f == b { x } else { 0 };
+                } else if b == self.next[f] {
+                    r += x;
+                } else if b == 0 {
+                    r += x * self.others(f);
+                }
+            }
+        }
+        r
+    }
+}
+
+/// Returns the number of necklaces whose representative is smaller than `t`.
+fn necklaces_below(t: &[usize], k: usize) -> usize {
+    let n = t.len();
+    // There is only the word of zeros with less than 2 letters.
+    if n == 0 || k < 2 {
+        return 0;
+    }
+    let automaton = Automaton::new(t, k);
+    let mut r = 0;
+    for d in 1 ..= n {
+        if n.is_multiple_of(d) {
+            r += crate::euler_totient(n / d) * automaton.count_above(d);
+        }
+    }
+    crate::necklace(n, k) - r / n
+}
+
+/// Returns the number of Lyndon words smaller than `t`.
+fn lyndons_below(t: &[usize], k: usize) -> usize {
+    let n = t.len();
+    if n == 0 || k < 2 {
+        return 0;
+    }
+    let automaton = Automaton::new(t, k);
+    let (mut p, mut m) = (0, 0);
+    for d in 1 ..= n {
+        if n.is_multiple_of(d) {
+            match crate::moebius(n / d) {
+                1 => p += automaton.count_above(d),
+                -1 => m += automaton.count_above(d),
+                _ => (),
+            }
+        }
+    }
+    crate::lyndon(n, k) - (p - m) / n
+}
+
+/// Returns the number of bracelets whose representative is smaller than `t`.
+///
+/// The bracelets whose representative is greater than or equal to `t` are counted with Burnside's
+/// lemma as the orbits of the words whose rotations and reversed rotations are all greater than or
+/// equal to `t`.
+fn bracelets_below(t: &[usize], k: usize) -> usize {
+    let n = t.len();
+    if n == 0 || k < 2 {
+        return 0;
+    }
+    let automaton = Automaton::new(t, k);
+    let mut x = [0; STATES * STATES];
+    let mut y = [0; STATES * STATES];
+    let rotations = automaton.count_rotations(&mut x, &mut y);
+    // Words fixed by a reflection are rotations of their reverse, so only their rotations need to
+    // be checked. Reflections are conjugate to one (for odd n) or two (for even n) of them.
+    let reflections = if n % 2 == 1 {
+        automaton.count_symmetric(&mut x, &mut y, n / 2, false, true)
+    } else {
+        let between = automaton.count_symmetric(&mut x, &mut y, n / 2, false, false);
+        let through = automaton.count_symmetric(&mut x, &mut y, n / 2 - 1, true, true);
+        (between + through) / 2
+    };
+    crate::bracelet(n, k) - (rotations / n + reflections) / 2
+}
+
+/// Writes the largest word whose number of smaller representatives is at most `p`.
+fn search(p: usize, k: usize, r: &mut [usize], below: fn(&[usize], usize) -> usize) {
+    r.fill(0);
+    for i in 0 .. r.len() {
+        // The number of smaller representatives increases with the letter.
+        let (mut lo, mut hi) = (0, k);
+        while hi - lo > 1 {
+            r[i] = lo + (hi - lo) / 2;
+            if below(r, k) <= p {
+                lo = r[i];
+            } else {
+                hi = r[i];
+            }
+        }
+        r[i] = lo;
+    }
+}
+
+/// Writes the necklace of a value to a slice.
+///
+/// The written necklace can be encoded with [`encode`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode_mut, encode};
+/// # let p = 5;
+/// # let n = 4;
+/// # let k = 2;
+/// let mut xs = vec![0; n];
+/// decode_mut(p, k, &mut xs);
+/// assert_eq!(encode(&xs, k), p);
+/// ```
+///
+/// See [`decode`] for a version that allocates a vector for the necklace.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= necklace(r.len(), k)`.
+pub fn decode_mut(p: usize, k: usize, r: &mut [usize]) {
+    debug_assert!(p < crate::necklace(r.len(), k), "Failed precondition");
+    search(p, k, r, necklaces_below);
+    debug_assert!(is_necklace(r));
+}
+
+/// Returns the necklace of a value.
+///
+/// The returned necklace can be encoded with [`encode`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode, encode};
+/// let p = 5;
+/// let n = 4;
+/// let k = 2;
+/// let xs = decode(p, n, k);
+/// assert_eq!(encode(&xs, k), p);
+/// ```
+///
+/// See [`decode_mut`] for a version that writes the necklace to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= necklace(n, k)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::decode;
+/// assert_eq!(decode(0, 4, 2), &[0, 0, 0, 0]);
+/// assert_eq!(decode(2, 4, 2), &[0, 0, 1, 1]);
+/// assert_eq!(decode(3, 4, 2), &[0, 1, 0, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode(p: usize, n: usize, k: usize) -> Vec<usize> {
+    let mut r = vec![0; n];
+    decode_mut(p, k, &mut r);
+    r
+}
+
+#[test]
+fn decode_ok() {
+    fn test(p: usize, n: usize, k: usize, r: &[usize]) {
+        assert_eq!(decode(p, n, k), r, "p={p} n={n} k={k}");
+    }
+    test(0, 0, 2, &[]);
+    test(0, 1, 2, &[0]);
+    test(1, 1, 2, &[1]);
+    test(0, 4, 2, &[0, 0, 0, 0]);
+    test(1, 4, 2, &[0, 0, 0, 1]);
+    test(2, 4, 2, &[0, 0, 1, 1]);
+    test(3, 4, 2, &[0, 1, 0, 1]);
+    test(4, 4, 2, &[0, 1, 1, 1]);
+    test(5, 4, 2, &[1, 1, 1, 1]);
+    test(0, 3, 3, &[0, 0, 0]);
+    test(3, 3, 3, &[0, 1, 1]);
+    test(5, 3, 3, &[0, 2, 1]);
+    test(10, 3, 3, &[2, 2, 2]);
+}
+
+/// Returns the value of a necklace.
+///
+/// The returned value can be decoded with [`decode`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode, encode};
+/// # let xs = &[0, 1, 0, 1];
+/// # let k = 2;
+/// let p = encode(xs, k);
+/// assert_eq!(decode(p, xs.len(), k), xs);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not the representative of its necklace or contains a value not
+/// less than `k`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::encode;
+/// assert_eq!(encode(&[0, 0, 0, 0], 2), 0);
+/// assert_eq!(encode(&[0, 0, 1, 1], 2), 2);
+/// assert_eq!(encode(&[0, 1, 0, 1], 2), 3);
+/// ```
+pub fn encode(xs: &[usize], k: usize) -> usize {
+    debug_assert!(is_necklace(xs), "Failed precondition");
+    debug_assert!(xs.iter().all(|&x| x < k), "Failed precondition");
+    necklaces_below(xs, k)
+}
+
+#[test]
+fn encode_ok() {
+    fn test(xs: &[usize], k: usize, p: usize) {
+        assert_eq!(encode(xs, k), p, "xs={xs:?} k={k}");
+    }
+    test(&[], 2, 0);
+    test(&[1], 2, 1);
+    test(&[0, 0, 0, 1], 2, 1);
+    test(&[0, 1, 1, 1], 2, 4);
+    test(&[1, 1, 1, 1], 2, 5);
+    test(&[0, 1, 2], 3, 4);
+    test(&[1, 1, 2], 3, 8);
+    test(&[0; 100], 1, 0);
+}
+
+/// Writes the Lyndon word of a value to a slice.
+///
+/// The written Lyndon word can be encoded with [`encode_lyndon`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode_lyndon_mut, encode_lyndon};
+/// # let p = 2;
+/// # let n = 4;
+/// # let k = 2;
+/// let mut xs = vec![0; n];
+/// decode_lyndon_mut(p, k, &mut xs);
+/// assert_eq!(encode_lyndon(&xs, k), p);
+/// ```
+///
+/// See [`decode_lyndon`] for a version that allocates a vector for the Lyndon word.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= lyndon(r.len(), k)`.
+pub fn decode_lyndon_mut(p: usize, k: usize, r: &mut [usize]) {
+    debug_assert!(p < crate::lyndon(r.len(), k), "Failed precondition");
+    search(p, k, r, lyndons_below);
+    debug_assert!(is_lyndon(r));
+}
+
+/// Returns the Lyndon word of a value.
+///
+/// The returned Lyndon word can be encoded with [`encode_lyndon`] to get back `p`.
+///
+/// See [`decode_lyndon_mut`] for a version that writes the Lyndon word to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= lyndon(n, k)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::decode_lyndon;
+/// assert_eq!(decode_lyndon(0, 4, 2), &[0, 0, 0, 1]);
+/// assert_eq!(decode_lyndon(1, 4, 2), &[0, 0, 1, 1]);
+/// assert_eq!(decode_lyndon(2, 4, 2), &[0, 1, 1, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_lyndon(p: usize, n: usize, k: usize) -> Vec<usize> {
+    let mut r = vec![0; n];
+    decode_lyndon_mut(p, k, &mut r);
+    r
+}
+
+#[test]
+fn decode_lyndon_ok() {
+    fn test(p: usize, n: usize, k: usize, r: &[usize]) {
+        assert_eq!(decode_lyndon(p, n, k), r, "p={p} n={n} k={k}");
+    }
+    test(0, 1, 2, &[0]);
+    test(1, 1, 2, &[1]);
+    test(0, 2, 2, &[0, 1]);
+    test(0, 4, 2, &[0, 0, 0, 1]);
+    test(2, 4, 2, &[0, 1, 1, 1]);
+    test(0, 3, 3, &[0, 0, 1]);
+    test(3, 3, 3, &[0, 1, 2]);
+    test(7, 3, 3, &[1, 2, 2]);
+}
+
+/// Returns the value of a Lyndon word.
+///
+/// The returned value can be decoded with [`decode_lyndon`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not a Lyndon word or contains a value not less than `k`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::encode_lyndon;
+/// assert_eq!(encode_lyndon(&[0, 0, 0, 1], 2), 0);
+/// assert_eq!(encode_lyndon(&[0, 0, 1, 1], 2), 1);
+/// assert_eq!(encode_lyndon(&[0, 1, 1, 1], 2), 2);
+/// ```
+pub fn encode_lyndon(xs: &[usize], k: usize) -> usize {
+    debug_assert!(is_lyndon(xs), "Failed precondition");
+    debug_assert!(xs.iter().all(|&x| x < k), "Failed precondition");
+    lyndons_below(xs, k)
+}
+
+#[test]
+fn encode_lyndon_ok() {
+    fn test(xs: &[usize], k: usize, p: usize) {
+        assert_eq!(encode_lyndon(xs, k), p, "xs={xs:?} k={k}");
+    }
+    test(&[0], 2, 0);
+    test(&[1], 2, 1);
+    test(&[0, 1], 2, 0);
+    test(&[0, 0, 1, 1], 2, 1);
+    test(&[0, 0, 2], 3, 1);
+    test(&[0, 2, 1], 3, 4);
+    test(&[1, 2, 2], 3, 7);
+}
+
+/// Writes the bracelet of a value to a slice.
+///
+/// The written bracelet can be encoded with [`encode_bracelet`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode_bracelet_mut, encode_bracelet};
+/// # let p = 5;
+/// # let n = 4;
+/// # let k = 3;
+/// let mut xs = vec![0; n];
+/// decode_bracelet_mut(p, k, &mut xs);
+/// assert_eq!(encode_bracelet(&xs, k), p);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= bracelet(r.len(), k)`.
+pub fn decode_bracelet_mut(p: usize, k: usize, r: &mut [usize]) {
+    debug_assert!(p < crate::bracelet(r.len(), k), "Failed precondition");
+    search(p, k, r, bracelets_below);
+    debug_assert!(is_bracelet(r));
+}
+
+/// Returns the bracelet of a value.
+///
+/// The returned bracelet can be encoded with [`encode_bracelet`] to get back `p`.
+///
+/// See [`decode_bracelet_mut`] for a version that writes the bracelet to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p >= bracelet(n, k)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::decode_bracelet;
+/// assert_eq!(decode_bracelet(0, 3, 3), &[0, 0, 0]);
+/// assert_eq!(decode_bracelet(4, 3, 3), &[0, 1, 2]);
+/// assert_eq!(decode_bracelet(5, 3, 3), &[0, 2, 2]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_bracelet(p: usize, n: usize, k: usize) -> Vec<usize> {
+    let mut r = vec![0; n];
+    decode_bracelet_mut(p, k, &mut r);
+    r
+}
+
+#[test]
+fn decode_bracelet_ok() {
+    fn test(p: usize, n: usize, k: usize, r: &[usize]) {
+        assert_eq!(decode_bracelet(p, n, k), r, "p={p} n={n} k={k}");
+    }
+    test(0, 0, 2, &[]);
+    test(1, 1, 2, &[1]);
+    test(0, 3, 3, &[0, 0, 0]);
+    test(3, 3, 3, &[0, 1, 1]);
+    test(4, 3, 3, &[0, 1, 2]);
+    test(5, 3, 3, &[0, 2, 2]);
+    test(9, 3, 3, &[2, 2, 2]);
+}
+
+/// Returns the value of a bracelet.
+///
+/// The returned value can be decoded with [`decode_bracelet`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not the representative of its bracelet or contains a value not
+/// less than `k`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::encode_bracelet;
+/// assert_eq!(encode_bracelet(&[0, 0, 0], 3), 0);
+/// assert_eq!(encode_bracelet(&[0, 1, 2], 3), 4);
+/// assert_eq!(encode_bracelet(&[0, 2, 2], 3), 5);
+/// ```
+pub fn encode_bracelet(xs: &[usize], k: usize) -> usize {
+    debug_assert!(is_bracelet(xs), "Failed precondition");
+    debug_assert!(xs.iter().all(|&x| x < k), "Failed precondition");
+    bracelets_below(xs, k)
+}
+
+#[test]
+fn encode_bracelet_ok() {
+    fn test(xs: &[usize], k: usize, p: usize) {
+        assert_eq!(encode_bracelet(xs, k), p, "xs={xs:?} k={k}");
+    }
+    test(&[], 2, 0);
+    test(&[1], 2, 1);
+    test(&[0, 1, 1], 3, 3);
+    test(&[0, 1, 2], 3, 4);
+    test(&[0, 2, 2], 3, 5);
+    test(&[2, 2, 2], 3, 9);
+    test(&[0; 100], 1, 0);
+}
+
+/// Iterates over all necklaces.
+///
+/// The necklaces are iterated in value order:
+///
+/// ```rust
+/// # use number_encoding::necklaces::{Iter, encode};
+/// # let n = 6;
+/// # let k = 3;
+/// let mut iter = Iter::new(n, k);
+/// let mut i = 0;
+/// while let Some(xs) = iter.next() {
+///     assert_eq!(encode(xs, k), i);
+///     i += 1;
+/// }
+/// ```
+///
+/// This uses the algorithm of Fredricksen, Kessler, and Maiorana, which takes constant amortized
+/// time per necklace.
+///
+/// # Examples
+///
+/// To iterate over Lyndon words only:
+///
+/// ```rust
+/// # use number_encoding::necklaces::{Iter, is_lyndon};
+/// # fn process(xs: &[usize]) {}
+/// # let n = 6;
+/// # let k = 3;
+/// let mut iter = Iter::new(n, k);
+/// while let Some(xs) = iter.next() {
+///     if is_lyndon(xs) {
+///         process(xs);
+///     }
+/// }
+/// ```
+///
+/// In a no-std environment, you can pass a buffer of size `N`:
+///
+/// ```rust
+/// # use number_encoding::necklaces::Iter;
+/// # const N: usize = 6;
+/// # let k = 3;
+/// let mut buffer = [0usize; N];
+/// let mut iter = Iter::new_with_buffer(&mut buffer[..], k);
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct Iter<T: BorrowMut<[usize]>> {
+    data: T,
+    k: usize,
+    state: IterState,
+    rank: usize,
+}
+
+enum IterState {
+    New,
+    Running,
+    Done,
+}
+
+#[cfg(feature = "alloc")]
+impl Iter<Vec<usize>> {
+    /// Constructs an iterator.
+    pub fn new(n: usize, k: usize) -> Iter<Vec<usize>> {
+        Iter::new_with_buffer(vec![0; n], k)
+    }
+}
+
+impl<T: BorrowMut<[usize]>> Iter<T> {
+    /// Constructs an iterator with a buffer.
+    pub fn new_with_buffer(mut buffer: T, k: usize) -> Iter<T> {
+        buffer.borrow_mut().fill(0);
+        let n = buffer.borrow().len();
+        let state = if k == 0 && n > 0 { IterState::Done } else { IterState::New };
+        Iter { data: buffer, k, state, rank: 0 }
+    }
+
+    /// Returns the value of the current necklace.
+    ///
+    /// The current necklace is the one last returned by [`next`](Iter::next), or the one it will
+    /// return if it was not called yet. After the end of the iteration, this is the number of
+    /// necklaces.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the necklace of a given value.
+    ///
+    /// The next call to [`next`](Iter::next) returns this necklace.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        decode_mut(rank, self.k, self.data.borrow_mut());
+        self.state = IterState::New;
+        self.rank = rank;
+    }
+
+    /// Returns the next necklace.
+    ///
+    /// If iteration is over, returns `None`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            IterState::New => self.state = IterState::Running,
+            IterState::Running => {
+                self.rank += 1;
+                if self.advance() {
+                    self.state = IterState::Done;
+                }
+            }
+            IterState::Done => (),
+        }
+        match self.state {
+            IterState::New => unreachable!(),
+            IterState::Running => Some(self.data.borrow()),
+            IterState::Done => None,
+        }
+    }
+
+    fn advance(&mut self) -> bool {
+        let xs = self.data.borrow_mut();
+        let n = xs.len();
+        loop {
+            let i = match xs.iter().rposition(|&x| x + 1 < self.k) {
+                None => return true,
+                Some(i) => i,
+            };
+            xs[i] += 1;
+            for j in i + 1 .. n {
+                xs[j] = xs[j - i - 1];
+            }
+            if n.is_multiple_of(i + 1) {
+                return false;
+            }
+        }
+    }
+}
+
+impl<T: BorrowMut<[usize]>> Lending for Iter<T> {
+    type Item = usize;
+
+    fn lend(&mut self) -> Option<&[usize]> {
+        self.next()
+    }
+}
+
+impl<T: BorrowMut<[usize]>> RankedIter for Iter<T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
+    }
+}
+
+#[test]
+fn iter_ok() {
+    fn test(n: usize, k: usize, r: &[&[usize]]) {
+        let mut iter = Iter::new(n, k);
+        let mut i = 0;
+        while let Some(xs) = iter.next() {
+            assert_eq!(xs, r[i], "n={n} k={k}");
+            i += 1;
+        }
+        assert_eq!(r.len(), i, "n={n} k={k}");
+    }
+    test(0, 0, &[&[]]);
+    test(1, 0, &[]);
+    test(0, 2, &[&[]]);
+    test(1, 2, &[&[0], &[1]]);
+    test(2, 2, &[&[0, 0], &[0, 1], &[1, 1]]);
+    test(
+        4,
+        2,
+        &[&[0, 0, 0, 0], &[0, 0, 0, 1], &[0, 0, 1, 1], &[0, 1, 0, 1], &[0, 1, 1, 1], &[1, 1, 1, 1]],
+    );
+    test(
+        3,
+        3,
+        &[
+            &[0, 0, 0],
+            &[0, 0, 1],
+            &[0, 0, 2],
+            &[0, 1, 1],
+            &[0, 1, 2],
+            &[0, 2, 1],
+            &[0, 2, 2],
+            &[1, 1, 1],
+            &[1, 1, 2],
+            &[1, 2, 2],
+            &[2, 2, 2],
+        ],
+    );
+}
+
+fn circular_free_count(n: usize) -> usize {
+    if n < 3 {
+        1
+    } else {
+        crate::factorial(n - 1) / 2
+    }
+}
+
+/// Applies the circular arrangement of the value `p` to the slice `xs`.
+///
+/// Circular arrangements are considered up to rotation, and represented by the rotation starting
+/// with the smallest element. There are `factorial(n - 1)` circular arrangements of `n > 0`
+/// elements.
+///
+/// The applied circular arrangement can be encoded with [`encode_circular`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode_circular_mut, encode_circular};
+/// # let mut xs = [0, 1, 2, 3];
+/// # let p = 4;
+/// decode_circular_mut(&mut xs, p);
+/// assert_eq!(encode_circular(&xs), p);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or `p` is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::decode_circular_mut;
+/// let mut xs = [0, 1, 2, 3];
+/// decode_circular_mut(&mut xs, 3);
+/// assert_eq!(xs, [0, 2, 3, 1]);
+/// ```
+pub fn decode_circular_mut<T: Ord>(xs: &mut [T], p: usize) {
+    if xs.is_empty() {
+        debug_assert_eq!(p, 0, "Failed precondition");
+        return;
+    }
+    crate::factoradics::decode_mut(&mut xs[1 ..], p);
+}
+
+/// Returns the value of a circular arrangement.
+///
+/// The returned value can be decoded with [`decode_circular_mut`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` does not contain distinct elements or does not start with its
+/// smallest element.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::encode_circular;
+/// assert_eq!(encode_circular(&[0, 1, 2, 3]), 0);
+/// assert_eq!(encode_circular(&[0, 2, 3, 1]), 3);
+/// assert_eq!(encode_circular(&[0, 3, 2, 1]), 5);
+/// ```
+pub fn encode_circular<T: Ord>(xs: &[T]) -> usize {
+    debug_assert!(xs.iter().skip(1).all(|x| x > &xs[0]), "Failed precondition");
+    if xs.is_empty() {
+        return 0;
+    }
+    crate::factoradics::encode(&xs[1 ..])
+}
+
+#[test]
+fn circular_ok() {
+    for n in 0 .. 7 {
+        let m = if n == 0 { 1 } else { crate::factorial(n - 1) };
+        let mut prev: Option<Vec<usize>> = None;
+        for p in 0 .. m {
+            let mut xs: Vec<_> = (0 .. n).collect();
+            decode_circular_mut(&mut xs, p);
+            assert!(prev.is_none_or(|prev| prev < xs), "n={n} p={p}");
+            assert_eq!(encode_circular(&xs), p, "n={n} p={p}");
+            prev = Some(xs);
+        }
+    }
+}
+
+/// Returns the number of circular arrangements up to reversal starting with `xs[.. i + 1]`.
+///
+/// The elements after `i` are the remaining elements.
+fn circular_free_completions<T: Ord>(xs: &[T], i: usize) -> usize {
+    let n = xs.len();
+    if n < 3 {
+        return 1;
+    }
+    if i == n - 1 {
+        return (xs[n - 1] > xs[1]) as usize;
+    }
+    let m = crate::factorial(n - i - 2);
+    m * xs[i + 1 ..].iter().filter(|&x| x > &xs[1]).count()
+}
+
+/// Applies the circular arrangement up to reversal of the value `p` to the slice `xs`.
+///
+/// Circular arrangements are considered up to rotation and reversal, and represented by the
+/// rotation starting with the smallest element and such that the second element is smaller than
+/// the last one. There are `factorial(n - 1) / 2` circular arrangements of `n > 2` elements.
+///
+/// The applied circular arrangement can be encoded with [`encode_circular_free`] to get back `p`.
+///
+/// ```rust
+/// # use number_encoding::necklaces::{decode_circular_free_mut, encode_circular_free};
+/// # let mut xs = [0, 1, 2, 3, 4];
+/// # let p = 7;
+/// decode_circular_free_mut(&mut xs, p);
+/// assert_eq!(encode_circular_free(&xs), p);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not increasing or `p` is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::decode_circular_free_mut;
+/// let mut xs = [0, 1, 2, 3];
+/// decode_circular_free_mut(&mut xs, 2);
+/// assert_eq!(xs, [0, 2, 1, 3]);
+/// ```
+pub fn decode_circular_free_mut<T: Ord>(xs: &mut [T], mut p: usize) {
+    let n = xs.len();
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    debug_assert!(p < circular_free_count(n), "Failed precondition");
+    for i in 1 .. n {
+        for j in i .. n {
+            xs[i ..= j].rotate_right(1);
+            let c = circular_free_completions(xs, i);
+            if p < c {
+                break;
+            }
+            p -= c;
+            xs[i ..= j].rotate_left(1);
+        }
+    }
+    debug_assert_eq!(p, 0);
+}
+
+/// Returns the value of a circular arrangement up to reversal.
+///
+/// The returned value can be decoded with [`decode_circular_free_mut`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` does not contain distinct elements, does not start with its
+/// smallest element, or does not have its second element smaller than its last.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::necklaces::encode_circular_free;
+/// assert_eq!(encode_circular_free(&[0, 1, 2, 3]), 0);
+/// assert_eq!(encode_circular_free(&[0, 1, 3, 2]), 1);
+/// assert_eq!(encode_circular_free(&[0, 2, 1, 3]), 2);
+/// ```
+pub fn encode_circular_free<T: Ord>(xs: &[T]) -> usize {
+    let n = xs.len();
+    debug_assert!(xs.iter().skip(1).all(|x| x > &xs[0]), "Failed precondition");
+    debug_assert!(n < 3 || xs[1] < xs[n - 1], "Failed precondition");
+    let mut r = 0;
+    for i in 1 .. n {
+        for j in i + 1 .. n {
+            if xs[j] >= xs[i] {
+                continue;
+            }
+            // Count the arrangements starting with xs[.. i] followed by xs[j].
+            let m = crate::factorial(n - i - 2);
+            if i == 1 {
+                r += m * xs[1 ..].iter().filter(|&x| x > &xs[j]).count();
+            } else {
+                let k = xs[i ..].iter().filter(|&x| x > &xs[1]).count();
+                r += m * (k - (xs[j] > xs[1]) as usize);
+            }
+        }
+    }
+    r
+}
+
+#[test]
+fn circular_free_ok() {
+    for n in 0 .. 8 {
+        let mut prev: Option<Vec<usize>> = None;
+        for p in 0 .. circular_free_count(n) {
+            let mut xs: Vec<_> = (0 .. n).collect();
+            decode_circular_free_mut(&mut xs, p);
+            assert!(n < 3 || xs[1] < xs[n - 1], "n={n} p={p}");
+            assert!(prev.is_none_or(|prev| prev < xs), "n={n} p={p}");
+            assert_eq!(encode_circular_free(&xs), p, "n={n} p={p}");
+            prev = Some(xs);
+        }
+    }
+}
+
+#[test]
+fn iter_rank_ok() {
+    let mut iter = Iter::new(6, 2);
+    while let Some(xs) = iter.next() {
+        let xs = xs.to_vec();
+        assert_eq!(encode(&xs, 2), iter.rank());
+    }
+    assert_eq!(iter.rank(), crate::necklace(6, 2));
+    iter.seek(5);
+    assert_eq!(iter.next(), Some(&decode(5, 6, 2)[..]));
+    assert_eq!(iter.rank(), 5);
+}
@@ -0,0 +1,488 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Orbit number system
+//!
+//! This permits to convert between orbits of objects under a permutation [`Group`] and values. The
+//! representative of an orbit is its object with the smallest value in the underlying number
+//! system, and orbits are ranked in the order of their representative.
+//!
+//! - [`Combinations`] ranks k-combinations (see [`combinadics`](crate::combinadics)) up to a group
+//!   acting on their elements (e.g. interchangeable card suits).
+//! - [`Permutations`] ranks multiset permutations (see [`multinadics`](crate::multinadics)) up to a
+//!   group acting on their positions (e.g. board rotations).
+//!
+//! Construction enumerates all objects once and stores the value of each representative, so
+//! encoding and decoding take logarithmic time in the number of orbits (plus the time to compute
+//! the representative).
+//!
+//! # Limits
+//!
+//! This module is meant for small groups acting on a moderate number of objects:
+//!
+//! - A [`Group`] stores all its elements, so its order should be at most a few thousands.
+//! - Construction takes time and memory proportional to the number of objects (e.g.
+//!   `combination(n, k)`) and time proportional to the number of orbits times the order of the
+//!   group, so the number of objects should be at most a few tens of millions.
+//! - Canonicalization applies every element of the group.
+//!
+//! Orbits of sequences under rotation or reflection are better ranked with the
+//! [`necklaces`](crate::necklaces) module, which takes polynomial time.
+//!
+//! See [wikipedia] for more information.
+//!
+//! [wikipedia]: https://en.wikipedia.org/wiki/Group_action#Orbits_and_stabilizers
+
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Permutation group.
+///
+/// A permutation of degree `n` is a slice `g` of length `n` containing each of `0 .. n` once. It
+/// maps `i` to `g[i]`.
+///
+/// # Examples
+///
+/// The rotations and reflections of a 2x2 board, whose cells are numbered in row-major order:
+///
+/// ```rust
+/// # use number_encoding::orbits::Group;
+/// let group = Group::new(4, &[&[1, 3, 0, 2], &[1, 0, 3, 2]]);
+/// assert_eq!(group.order(), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Group {
+    degree: usize,
+    elements: Vec<Vec<usize>>,
+}
+
+impl Group {
+    /// Constructs the group of degree `n` generated by some permutations.
+    ///
+    /// This takes time and memory proportional to the order of the group (times `n`).
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if a generator is not a permutation of degree `n`.
+    pub fn new(n: usize, generators: &[&[usize]]) -> Group {
+        debug_assert!(generators.iter().all(|g| is_permutation(n, g)), "Failed precondition");
+        let identity: Vec<_> = (0 .. n).collect();
+        let mut seen = BTreeSet::new();
+        let _ = seen.insert(identity.clone());
+        let mut elements = vec![identity];
+        let mut i = 0;
+        while i < elements.len() {
+            for g in generators {
+                let h: Vec<_> = elements[i].iter().map(|&x| g[x]).collect();
+                if seen.insert(h.clone()) {
+                    elements.push(h);
+                }
+            }
+            i += 1;
+        }
+        Group { degree: n, elements }
+    }
+
+    /// Returns the degree of the group.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the number of elements of the group.
+    pub fn order(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns the elements of the group.
+    ///
+    /// The identity comes first.
+    pub fn elements(&self) -> impl Iterator<Item = &[usize]> {
+        self.elements.iter().map(|g| &g[..])
+    }
+}
+
+fn is_permutation(n: usize, g: &[usize]) -> bool {
+    g.len() == n && (0 .. n).all(|i| g.contains(&i))
+}
+
+#[test]
+fn group_ok() {
+    fn test(n: usize, generators: &[&[usize]], order: usize) {
+        let group = Group::new(n, generators);
+        assert_eq!(group.degree(), n);
+        assert_eq!(group.order(), order, "generators={generators:?}");
+        let elements: BTreeSet<_> = group.elements().collect();
+        assert_eq!(elements.len(), order);
+        assert_eq!(group.elements().next().unwrap(), (0 .. n).collect::<Vec<_>>());
+        for g in group.elements() {
+            assert!(is_permutation(n, g));
+        }
+    }
+    test(0, &[], 1);
+    test(3, &[], 1);
+    test(3, &[&[0, 1, 2]], 1);
+    test(4, &[&[1, 2, 3, 0]], 4);
+    test(4, &[&[1, 3, 0, 2], &[1, 0, 3, 2]], 8);
+    test(4, &[&[1, 0, 2, 3], &[1, 2, 3, 0]], 24);
+    test(6, &[&[1, 0, 2, 3, 4, 5], &[0, 1, 3, 2, 4, 5]], 4);
+}
+
+/// Ranks k-combinations up to a group acting on their elements.
+///
+/// The group has degree `n` and the k-combinations are those of `0 .. n`. The representative of an
+/// orbit is its k-combination with the smallest [`combinadics`](crate::combinadics) value.
+///
+/// # Examples
+///
+/// Hands of 2 cards from a deck of 2 ranks and 4 interchangeable suits (card `4 * r + s` has rank
+/// `r` and suit `s`):
+///
+/// ```rust
+/// # use number_encoding::orbits::{Combinations, Group};
+/// let cycle: Vec<_> = (0 .. 8).map(|x| x / 4 * 4 + (x + 1) % 4).collect();
+/// let swap: Vec<_> = (0 .. 8).map(|x| x / 4 * 4 + [1, 0, 2, 3][x % 4]).collect();
+/// let orbits = Combinations::new(Group::new(8, &[&cycle, &swap]), 2);
+/// // Pairs of each rank, and suited or offsuit hands of both ranks.
+/// assert_eq!(orbits.count(), 4);
+/// for p in 0 .. orbits.count() {
+///     assert_eq!(orbits.encode(&orbits.decode(p)), p);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Combinations {
+    group: Group,
+    k: usize,
+    representatives: Vec<usize>,
+}
+
+impl Combinations {
+    /// Constructs the orbits of k-combinations under a group.
+    ///
+    /// This takes time and memory proportional to `combination(n, k)`, and time proportional to
+    /// the number of orbits times the order of the group (times `k`).
+    pub fn new(group: Group, k: usize) -> Combinations {
+        let n = group.degree();
+        let total = crate::combination(n, k);
+        let mut representatives = Vec::new();
+        let mut seen = vec![false; total];
+        let mut iter = crate::combinadics::Iter::new(k);
+        let mut image = vec![0; k];
+        for p in 0 .. total {
+            if !seen[p] {
+                // The first k-combination of an orbit is its representative.
+                representatives.push(p);
+                for g in group.elements() {
+                    apply_elements(g, iter.get(), &mut image);
+                    seen[crate::combinadics::encode(&image)] = true;
+                }
+            }
+            iter.advance();
+        }
+        Combinations { group, k, representatives }
+    }
+
+    /// Returns the group.
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    /// Returns the number of orbits.
+    pub fn count(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// Replaces a k-combination by the representative of its orbit.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not an increasing slice of length `k` with elements less
+    /// than `n`.
+    pub fn canonicalize(&self, xs: &mut [usize]) {
+        debug_assert!(self.is_valid(xs), "Failed precondition");
+        let mut best = xs.to_vec();
+        let mut image = vec![0; xs.len()];
+        for g in self.group.elements() {
+            apply_elements(g, xs, &mut image);
+            if colex_cmp(&image, &best).is_lt() {
+                best.copy_from_slice(&image);
+            }
+        }
+        xs.copy_from_slice(&best);
+    }
+
+    /// Writes the representative of the orbit of a value to a slice.
+    ///
+    /// The written representative can be encoded with [`encode`](Combinations::encode) to get back
+    /// `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `p` is out of range or `r.len() != k`.
+    pub fn decode_mut(&self, p: usize, r: &mut [usize]) {
+        debug_assert!(p < self.count(), "Failed precondition");
+        crate::combinadics::decode_mut(self.representatives[p], self.k, r);
+    }
+
+    /// Returns the representative of the orbit of a value.
+    ///
+    /// The returned representative can be encoded with [`encode`](Combinations::encode) to get
+    /// back `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `p` is out of range.
+    pub fn decode(&self, p: usize) -> Vec<usize> {
+        let mut r = vec![0; self.k];
+        self.decode_mut(p, &mut r);
+        r
+    }
+
+    /// Returns the value of the orbit of a k-combination.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not an increasing slice of length `k` with elements less
+    /// than `n`.
+    pub fn encode(&self, xs: &[usize]) -> usize {
+        let mut xs = xs.to_vec();
+        self.canonicalize(&mut xs);
+        let p = crate::combinadics::encode(&xs);
+        self.representatives.binary_search(&p).unwrap()
+    }
+
+    fn is_valid(&self, xs: &[usize]) -> bool {
+        xs.len() == self.k
+            && crate::is_ordered_set(xs)
+            && xs.last().is_none_or(|&x| x < self.group.degree())
+    }
+}
+
+fn apply_elements(g: &[usize], xs: &[usize], r: &mut [usize]) {
+    for (y, &x) in r.iter_mut().zip(xs) {
+        *y = g[x];
+    }
+    r.sort_unstable();
+}
+
+fn colex_cmp(xs: &[usize], ys: &[usize]) -> core::cmp::Ordering {
+    xs.iter().rev().cmp(ys.iter().rev())
+}
+
+#[test]
+fn combinations_ok() {
+    fn test(n: usize, generators: &[&[usize]], k: usize, count: usize) {
+        let orbits = Combinations::new(Group::new(n, generators), k);
+        assert_eq!(orbits.count(), count, "n={n} k={k}");
+        let mut seen = vec![false; count];
+        let mut iter = crate::combinadics::Iter::new(k);
+        for _ in 0 .. crate::combination(n, k) {
+            let xs = iter.get();
+            let p = orbits.encode(xs);
+            seen[p] = true;
+            let r = orbits.decode(p);
+            assert!(crate::combinadics::encode(&r) <= crate::combinadics::encode(xs));
+            assert_eq!(orbits.encode(&r), p);
+            for g in orbits.group().elements() {
+                let mut ys = vec![0; k];
+                apply_elements(g, xs, &mut ys);
+                assert_eq!(orbits.encode(&ys), p);
+            }
+            iter.advance();
+        }
+        assert!(seen.iter().all(|&x| x));
+    }
+    test(4, &[], 2, 6);
+    test(4, &[&[1, 2, 3, 0]], 2, 2);
+    test(5, &[&[1, 2, 3, 4, 0]], 2, 2);
+    test(6, &[&[1, 2, 3, 4, 5, 0]], 3, 4);
+    test(6, &[&[1, 2, 3, 4, 5, 0], &[5, 4, 3, 2, 1, 0]], 3, 3);
+    test(4, &[&[1, 0, 2, 3], &[1, 2, 3, 0]], 2, 1);
+}
+
+/// Ranks multiset permutations up to a group acting on their positions.
+///
+/// The group has degree `n` and the multiset permutations are those of a non-decreasing slice of
+/// length `n`. A permutation `g` maps a multiset permutation `xs` to `ys` such that `ys[g[i]]` is
+/// `xs[i]`. The representative of an orbit is its multiset permutation with the smallest
+/// [`multinadics`](crate::multinadics) value.
+///
+/// # Examples
+///
+/// Boards of 2x2 cells with 2 black and 2 white stones up to rotation and reflection:
+///
+/// ```rust
+/// # use number_encoding::orbits::{Group, Permutations};
+/// let group = Group::new(4, &[&[1, 3, 0, 2], &[1, 0, 3, 2]]);
+/// let orbits = Permutations::new(group, &[0, 0, 1, 1]);
+/// // Stones of the same color are either adjacent or diagonal.
+/// assert_eq!(orbits.count(), 2);
+/// assert_eq!(orbits.decode(0), &[0, 0, 1, 1]);
+/// assert_eq!(orbits.decode(1), &[0, 1, 1, 0]);
+/// assert_eq!(orbits.encode(&[1, 0, 1, 0]), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Permutations<T> {
+    group: Group,
+    base: Vec<T>,
+    representatives: Vec<usize>,
+}
+
+impl<T: Clone + Ord> Permutations<T> {
+    /// Constructs the orbits of multiset permutations of a non-decreasing slice under a group.
+    ///
+    /// This takes time and memory proportional to `multinomial(xs)`, and time proportional to the
+    /// number of orbits times the order of the group (times `n`).
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not non-decreasing or its length is not the group degree.
+    pub fn new(group: Group, xs: &[T]) -> Permutations<T> {
+        debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+        debug_assert_eq!(xs.len(), group.degree(), "Failed precondition");
+        let base = xs.to_vec();
+        let mut representatives = Vec::new();
+        let mut seen = vec![false; crate::multinomial(xs)];
+        let mut data = base.clone();
+        let mut image = base.clone();
+        let mut iter = crate::multinadics::Iter::new(&mut data);
+        let mut p = 0;
+        while let Some(xs) = iter.next() {
+            if !seen[p] {
+                // The first multiset permutation of an orbit is its representative.
+                representatives.push(p);
+                for g in group.elements() {
+                    apply_positions(g, xs, &mut image);
+                    seen[crate::multinadics::encode(&image)] = true;
+                }
+            }
+            p += 1;
+        }
+        Permutations { group, base, representatives }
+    }
+
+    /// Returns the group.
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    /// Returns the number of orbits.
+    pub fn count(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// Replaces a multiset permutation by the representative of its orbit.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not a permutation of the base multiset.
+    pub fn canonicalize(&self, xs: &mut [T]) {
+        debug_assert!(self.is_valid(xs), "Failed precondition");
+        let mut best = xs.to_vec();
+        let mut image = xs.to_vec();
+        for g in self.group.elements() {
+            apply_positions(g, xs, &mut image);
+            if image < best {
+                best.clone_from_slice(&image);
+            }
+        }
+        xs.clone_from_slice(&best);
+    }
+
+    /// Writes the representative of the orbit of a value to a slice.
+    ///
+    /// The written representative can be encoded with [`encode`](Permutations::encode) to get back
+    /// `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `p` is out of range or `r.len() != n`.
+    pub fn decode_mut(&self, p: usize, r: &mut [T]) {
+        debug_assert!(p < self.count(), "Failed precondition");
+        r.clone_from_slice(&self.base);
+        crate::multinadics::decode_mut(r, self.representatives[p]);
+    }
+
+    /// Returns the representative of the orbit of a value.
+    ///
+    /// The returned representative can be encoded with [`encode`](Permutations::encode) to get
+    /// back `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `p` is out of range.
+    pub fn decode(&self, p: usize) -> Vec<T> {
+        let mut r = self.base.clone();
+        self.decode_mut(p, &mut r);
+        r
+    }
+
+    /// Returns the value of the orbit of a multiset permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `xs` is not a permutation of the base multiset.
+    pub fn encode(&self, xs: &[T]) -> usize {
+        let mut xs = xs.to_vec();
+        self.canonicalize(&mut xs);
+        let p = crate::multinadics::encode(&xs);
+        self.representatives.binary_search(&p).unwrap()
+    }
+
+    fn is_valid(&self, xs: &[T]) -> bool {
+        let mut ys = xs.to_vec();
+        ys.sort();
+        ys == self.base
+    }
+}
+
+fn apply_positions<T: Clone>(g: &[usize], xs: &[T], r: &mut [T]) {
+    for (i, x) in xs.iter().enumerate() {
+        r[g[i]] = x.clone();
+    }
+}
+
+#[test]
+fn permutations_ok() {
+    fn test(generators: &[&[usize]], xs: &[usize], count: usize) {
+        let n = xs.len();
+        let orbits = Permutations::new(Group::new(n, generators), xs);
+        assert_eq!(orbits.count(), count, "xs={xs:?}");
+        let mut seen = vec![false; count];
+        let mut data = xs.to_vec();
+        let mut iter = crate::multinadics::Iter::new(&mut data);
+        while let Some(xs) = iter.next() {
+            let p = orbits.encode(xs);
+            seen[p] = true;
+            let r = orbits.decode(p);
+            assert!(r[..] <= *xs);
+            assert_eq!(orbits.encode(&r), p);
+            for g in orbits.group().elements() {
+                let mut ys = xs.to_vec();
+                apply_positions(g, xs, &mut ys);
+                assert_eq!(orbits.encode(&ys), p);
+            }
+        }
+        assert!(seen.iter().all(|&x| x));
+    }
+    let square: &[&[usize]] = &[&[1, 3, 0, 2], &[1, 0, 3, 2]];
+    test(&[], &[0, 0, 1], 3);
+    test(&[&[1, 2, 0]], &[0, 0, 1], 1);
+    test(&[&[1, 2, 3, 0]], &[0, 0, 1, 1], 2);
+    test(&[&[1, 2, 3, 0]], &[0, 1, 2, 3], 6);
+    test(square, &[0, 0, 1, 1], 2);
+    test(square, &[0, 0, 0, 1], 1);
+    test(square, &[0, 1, 2, 3], 3);
+    test(&[&[1, 2, 3, 4, 5, 0], &[5, 4, 3, 2, 1, 0]], &[0, 0, 0, 1, 1, 2], 6);
+}
@@ -0,0 +1,561 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pairing functions
+//!
+//! This permits to convert between pairs (or tuples) of values and single values. Three orders are
+//! provided:
+//!
+//! - [Cantor] enumerates pairs by diagonals of increasing sum.
+//! - [Szudzik] enumerates pairs by shells of increasing maximum, such that the pairs with maximum
+//!   less than `m` are the first `m * m` values.
+//! - [Rosenberg-Strong] also enumerates by shells of increasing maximum, but each shell is
+//!   traversed continuously, and it generalizes to tuples of length `d` such that the tuples with
+//!   maximum less than `m` are the first `m.pow(d)` values.
+//!
+//! Tuples for Cantor and Szudzik are paired from left to right: the value of `[x, y, z]` is the
+//! value of the pair of the value of `[x, y]` and `z`. Tuples of length 1 are their single
+//! element and the empty tuple is zero.
+//!
+//! The encoding functions return `None` if the value overflows. The decoding functions never
+//! overflow.
+//!
+//! The [`u128`](mod@u128) module provides the same functions with `u128` values.
+//!
+//! [Cantor]: https://en.wikipedia.org/wiki/Pairing_function#Cantor_pairing_function
+//! [Szudzik]: http://szudzik.com/ElegantPairing.pdf
+//! [Rosenberg-Strong]: https://arxiv.org/abs/1706.04129
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+macro_rules! pairing {
+    ($path:literal) => {
+        /// Returns the triangular number of `w` (i.e. `w * (w + 1) / 2`).
+        pub(crate) fn triangle(w: Int) -> Option<Int> {
+            if w % 2 == 0 {
+                (w / 2).checked_mul(w.checked_add(1)?)
+            } else {
+                w.checked_mul(w / 2 + 1)
+            }
+        }
+
+        /// Returns the largest `w` such that `triangle(w) <= z` and the remainder.
+        pub(crate) fn triangle_root(z: Int) -> (Int, Int) {
+            let mut w = 2 * (z / 2).isqrt();
+            while triangle(w + 1).is_some_and(|t| t <= z) {
+                w += 1;
+            }
+            while triangle(w).is_none_or(|t| t > z) {
+                w -= 1;
+            }
+            (w, z - triangle(w).unwrap())
+        }
+
+        #[test]
+        fn triangle_root_ok() {
+            let mut z = 0;
+            for w in 0 .. 100 {
+                for i in 0 ..= w {
+                    assert_eq!(triangle_root(z), (w, i), "z={z}");
+                    z += 1;
+                }
+            }
+            for z in (Int::MAX - 1000 ..= Int::MAX).chain([Int::MAX / 2, Int::MAX / 2 + 1]) {
+                let (w, i) = triangle_root(z);
+                assert!(i <= w, "z={z}");
+                assert_eq!(triangle(w).unwrap() + i, z, "z={z}");
+            }
+        }
+
+        /// Returns the integer `d`-th root of `z`.
+        fn root(z: Int, d: u32) -> Int {
+            debug_assert!(d > 0);
+            let mut r: Int = 0;
+            for i in (0 .. Int::BITS.div_ceil(d)).rev() {
+                let c = r | 1 << i;
+                if c.checked_pow(d).is_some_and(|x| x <= z) {
+                    r = c;
+                }
+            }
+            r
+        }
+
+        #[test]
+        fn root_ok() {
+            for z in (0 .. 1000).chain(Int::MAX - 1000 ..= Int::MAX) {
+                for d in 1 .. 10 {
+                    let r = root(z, d);
+                    assert!(r.pow(d) <= z, "z={z} d={d}");
+                    let next = r.checked_add(1).and_then(|r| r.checked_pow(d));
+                    assert!(next.is_none_or(|x| x > z), "z={z} d={d}");
+                }
+            }
+        }
+
+        /// Returns the Cantor value of a pair.
+        ///
+        /// The returned value can be decoded with [`decode_cantor`] to get back `(x, y)`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(encode_cantor(0, 0), Some(0));
+        /// assert_eq!(encode_cantor(1, 0), Some(1));
+        /// assert_eq!(encode_cantor(0, 1), Some(2));
+        /// assert_eq!(encode_cantor(2, 0), Some(3));
+        /// assert_eq!(encode_cantor(1, 1), Some(4));
+        /// assert_eq!(encode_cantor(0, 2), Some(5));
+        /// ```
+        pub fn encode_cantor(x: Int, y: Int) -> Option<Int> {
+            triangle(x.checked_add(y)?)?.checked_add(y)
+        }
+
+        /// Returns the pair of a Cantor value.
+        ///
+        /// The returned pair can be encoded with [`encode_cantor`] to get back `z`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(decode_cantor(4), (1, 1));
+        /// assert_eq!(decode_cantor(5), (0, 2));
+        /// ```
+        pub fn decode_cantor(z: Int) -> (Int, Int) {
+            let (w, y) = triangle_root(z);
+            (w - y, y)
+        }
+
+        #[test]
+        fn cantor_ok() {
+            let mut z = 0;
+            for w in 0 .. 50 {
+                for y in 0 ..= w {
+                    assert_eq!(encode_cantor(w - y, y), Some(z));
+                    assert_eq!(decode_cantor(z), (w - y, y));
+                    z += 1;
+                }
+            }
+            for z in Int::MAX - 1000 ..= Int::MAX {
+                let (x, y) = decode_cantor(z);
+                assert_eq!(encode_cantor(x, y), Some(z), "z={z}");
+            }
+            let (x, y) = decode_cantor(Int::MAX);
+            assert_eq!(encode_cantor(x + 1, y), None);
+            assert_eq!(encode_cantor(Int::MAX, 1), None);
+        }
+
+        /// Returns the Szudzik value of a pair.
+        ///
+        /// The returned value can be decoded with [`decode_szudzik`] to get back `(x, y)`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(encode_szudzik(0, 0), Some(0));
+        /// assert_eq!(encode_szudzik(0, 1), Some(1));
+        /// assert_eq!(encode_szudzik(1, 0), Some(2));
+        /// assert_eq!(encode_szudzik(1, 1), Some(3));
+        /// assert_eq!(encode_szudzik(0, 2), Some(4));
+        /// ```
+        pub fn encode_szudzik(x: Int, y: Int) -> Option<Int> {
+            if x < y {
+                y.checked_mul(y)?.checked_add(x)
+            } else {
+                x.checked_mul(x)?.checked_add(x)?.checked_add(y)
+            }
+        }
+
+        /// Returns the pair of a Szudzik value.
+        ///
+        /// The returned pair can be encoded with [`encode_szudzik`] to get back `z`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(decode_szudzik(3), (1, 1));
+        /// assert_eq!(decode_szudzik(4), (0, 2));
+        /// ```
+        pub fn decode_szudzik(z: Int) -> (Int, Int) {
+            let m = z.isqrt();
+            let l = z - m * m;
+            if l < m {
+                (l, m)
+            } else {
+                (m, l - m)
+            }
+        }
+
+        #[test]
+        fn szudzik_ok() {
+            let mut z = 0;
+            for m in 0 .. 50 {
+                let shell = (0 .. m).map(|x| (x, m)).chain((0 ..= m).map(|y| (m, y)));
+                for (x, y) in shell {
+                    assert_eq!(encode_szudzik(x, y), Some(z));
+                    assert_eq!(decode_szudzik(z), (x, y));
+                    z += 1;
+                }
+            }
+            for z in Int::MAX - 1000 ..= Int::MAX {
+                let (x, y) = decode_szudzik(z);
+                assert_eq!(encode_szudzik(x, y), Some(z), "z={z}");
+            }
+            let (x, y) = decode_szudzik(Int::MAX);
+            assert_eq!(encode_szudzik(x, y + 1), None);
+        }
+
+        /// Returns the Rosenberg-Strong value of a pair.
+        ///
+        /// The returned value can be decoded with [`decode_rosenberg_strong`] to get back
+        /// `(x, y)`.
+        ///
+        /// This is the special case of [`encode_rosenberg_strong_tuple`] with `d = 2`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(encode_rosenberg_strong(0, 0), Some(0));
+        /// assert_eq!(encode_rosenberg_strong(0, 1), Some(1));
+        /// assert_eq!(encode_rosenberg_strong(1, 1), Some(2));
+        /// assert_eq!(encode_rosenberg_strong(1, 0), Some(3));
+        /// assert_eq!(encode_rosenberg_strong(0, 2), Some(4));
+        /// ```
+        pub fn encode_rosenberg_strong(x: Int, y: Int) -> Option<Int> {
+            encode_rosenberg_strong_tuple(&[x, y])
+        }
+
+        /// Returns the pair of a Rosenberg-Strong value.
+        ///
+        /// The returned pair can be encoded with [`encode_rosenberg_strong`] to get back `z`.
+        ///
+        /// This is the special case of [`decode_rosenberg_strong_tuple_mut`] with `d = 2`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(decode_rosenberg_strong(2), (1, 1));
+        /// assert_eq!(decode_rosenberg_strong(3), (1, 0));
+        /// ```
+        pub fn decode_rosenberg_strong(z: Int) -> (Int, Int) {
+            let mut xs = [0; 2];
+            decode_rosenberg_strong_tuple_mut(z, &mut xs);
+            (xs[0], xs[1])
+        }
+
+        #[test]
+        fn rosenberg_strong_ok() {
+            let mut z = 0;
+            for m in 0 .. 50 {
+                let shell = (0 .. m).map(|x| (x, m)).chain((0 ..= m).rev().map(|y| (m, y)));
+                for (x, y) in shell {
+                    assert_eq!(encode_rosenberg_strong(x, y), Some(z));
+                    assert_eq!(decode_rosenberg_strong(z), (x, y));
+                    z += 1;
+                }
+            }
+            for z in Int::MAX - 1000 ..= Int::MAX {
+                let (x, y) = decode_rosenberg_strong(z);
+                assert_eq!(encode_rosenberg_strong(x, y), Some(z), "z={z}");
+            }
+            let (x, y) = decode_rosenberg_strong(Int::MAX);
+            assert_eq!(encode_rosenberg_strong(x + 1, y), None);
+        }
+
+        /// Folds a tuple from left to right with a pairing function.
+        fn encode_tuple(xs: &[Int], pair: fn(Int, Int) -> Option<Int>) -> Option<Int> {
+            match xs.split_first() {
+                None => Some(0),
+                Some((&x, xs)) => xs.iter().try_fold(x, |z, &x| pair(z, x)),
+            }
+        }
+
+        /// Unfolds a tuple from right to left with an unpairing function.
+        fn decode_tuple_mut(mut z: Int, xs: &mut [Int], unpair: fn(Int) -> (Int, Int)) {
+            debug_assert!(!xs.is_empty() || z == 0, "Failed precondition");
+            let Some((first, xs)) = xs.split_first_mut() else { return };
+            for x in xs.iter_mut().rev() {
+                (z, *x) = unpair(z);
+            }
+            *first = z;
+        }
+
+        /// Returns the Cantor value of a tuple.
+        ///
+        /// The returned value can be decoded with [`decode_cantor_tuple`] to get back `xs`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(encode_cantor_tuple(&[]), Some(0));
+        /// assert_eq!(encode_cantor_tuple(&[3]), Some(3));
+        /// assert_eq!(encode_cantor_tuple(&[1, 1]), Some(4));
+        /// assert_eq!(encode_cantor_tuple(&[1, 1, 0]), Some(10));
+        /// ```
+        pub fn encode_cantor_tuple(xs: &[Int]) -> Option<Int> {
+            encode_tuple(xs, encode_cantor)
+        }
+
+        /// Writes the tuple of a Cantor value to a slice.
+        ///
+        /// The written tuple can be encoded with [`encode_cantor_tuple`] to get back `z`.
+        ///
+        /// See [`decode_cantor_tuple`] for a version that allocates a vector for the tuple.
+        ///
+        /// # Panics
+        ///
+        /// Panics in debug mode if `xs.is_empty() && z > 0`.
+        pub fn decode_cantor_tuple_mut(z: Int, xs: &mut [Int]) {
+            decode_tuple_mut(z, xs, decode_cantor);
+        }
+
+        /// Returns the tuple of length `d` of a Cantor value.
+        ///
+        /// The returned tuple can be encoded with [`encode_cantor_tuple`] to get back `z`.
+        ///
+        /// See [`decode_cantor_tuple_mut`] for a version that writes the tuple to a provided
+        /// slice.
+        ///
+        /// # Panics
+        ///
+        /// Panics in debug mode if `d == 0 && z > 0`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(decode_cantor_tuple(10, 3), &[1, 1, 0]);
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn decode_cantor_tuple(z: Int, d: usize) -> Vec<Int> {
+            let mut xs = vec![0; d];
+            decode_cantor_tuple_mut(z, &mut xs);
+            xs
+        }
+
+        #[test]
+        fn cantor_tuple_ok() {
+            for d in 0 .. 5 {
+                for z in 0 .. if d == 0 { 1 } else { 1000 } {
+                    let xs = decode_cantor_tuple(z, d);
+                    assert_eq!(encode_cantor_tuple(&xs), Some(z), "z={z} d={d}");
+                }
+            }
+            assert_eq!(encode_cantor_tuple(&[0, 0, Int::MAX]), None);
+        }
+
+        /// Returns the Szudzik value of a tuple.
+        ///
+        /// The returned value can be decoded with [`decode_szudzik_tuple`] to get back `xs`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(encode_szudzik_tuple(&[]), Some(0));
+        /// assert_eq!(encode_szudzik_tuple(&[3]), Some(3));
+        /// assert_eq!(encode_szudzik_tuple(&[1, 1]), Some(3));
+        /// assert_eq!(encode_szudzik_tuple(&[1, 1, 0]), Some(12));
+        /// ```
+        pub fn encode_szudzik_tuple(xs: &[Int]) -> Option<Int> {
+            encode_tuple(xs, encode_szudzik)
+        }
+
+        /// Writes the tuple of a Szudzik value to a slice.
+        ///
+        /// The written tuple can be encoded with [`encode_szudzik_tuple`] to get back `z`.
+        ///
+        /// See [`decode_szudzik_tuple`] for a version that allocates a vector for the tuple.
+        ///
+        /// # Panics
+        ///
+        /// Panics in debug mode if `xs.is_empty() && z > 0`.
+        pub fn decode_szudzik_tuple_mut(z: Int, xs: &mut [Int]) {
+            decode_tuple_mut(z, xs, decode_szudzik);
+        }
+
+        /// Returns the tuple of length `d` of a Szudzik value.
+        ///
+        /// The returned tuple can be encoded with [`encode_szudzik_tuple`] to get back `z`.
+        ///
+        /// See [`decode_szudzik_tuple_mut`] for a version that writes the tuple to a provided
+        /// slice.
+        ///
+        /// # Panics
+        ///
+        /// Panics in debug mode if `d == 0 && z > 0`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(decode_szudzik_tuple(12, 3), &[1, 1, 0]);
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn decode_szudzik_tuple(z: Int, d: usize) -> Vec<Int> {
+            let mut xs = vec![0; d];
+            decode_szudzik_tuple_mut(z, &mut xs);
+            xs
+        }
+
+        #[test]
+        fn szudzik_tuple_ok() {
+            for d in 0 .. 5 {
+                for z in 0 .. if d == 0 { 1 } else { 1000 } {
+                    let xs = decode_szudzik_tuple(z, d);
+                    assert_eq!(encode_szudzik_tuple(&xs), Some(z), "z={z} d={d}");
+                }
+            }
+            assert_eq!(encode_szudzik_tuple(&[0, 0, Int::MAX]), None);
+        }
+
+        /// Returns the Rosenberg-Strong value of a tuple.
+        ///
+        /// The returned value can be decoded with [`decode_rosenberg_strong_tuple`] to get back
+        /// `xs`. The value is less than `(m + 1).pow(d)` where `m` is the maximum of `xs` and `d`
+        /// its length.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(encode_rosenberg_strong_tuple(&[]), Some(0));
+        /// assert_eq!(encode_rosenberg_strong_tuple(&[3]), Some(3));
+        /// assert_eq!(encode_rosenberg_strong_tuple(&[1, 1]), Some(2));
+        /// assert_eq!(encode_rosenberg_strong_tuple(&[1, 1, 0]), Some(6));
+        /// assert_eq!(encode_rosenberg_strong_tuple(&[1, 1, 1]), Some(3));
+        /// ```
+        pub fn encode_rosenberg_strong_tuple(xs: &[Int]) -> Option<Int> {
+            let Some((&first, xs)) = xs.split_first() else { return Some(0) };
+            let mut z = first;
+            let mut m = first;
+            for (k, &x) in (2 ..).zip(xs) {
+                m = core::cmp::max(m, x);
+                // The value of the tuple with the same prefix and last element m.
+                z = z.checked_add(m.checked_pow(k)?)?;
+                if x < m {
+                    let p = m.pow(k - 1);
+                    let q = (m + 1).checked_pow(k - 1)?;
+                    z = z.checked_add((m - x).checked_mul(q - p)?)?;
+                }
+            }
+            Some(z)
+        }
+
+        /// Writes the tuple of a Rosenberg-Strong value to a slice.
+        ///
+        /// The written tuple can be encoded with [`encode_rosenberg_strong_tuple`] to get back
+        /// `z`.
+        ///
+        /// See [`decode_rosenberg_strong_tuple`] for a version that allocates a vector for the
+        /// tuple.
+        ///
+        /// # Panics
+        ///
+        /// Panics in debug mode if `xs.is_empty() && z > 0`.
+        pub fn decode_rosenberg_strong_tuple_mut(mut z: Int, xs: &mut [Int]) {
+            debug_assert!(!xs.is_empty() || z == 0, "Failed precondition");
+            let Some((first, xs)) = xs.split_first_mut() else { return };
+            for (i, x) in xs.iter_mut().enumerate().rev() {
+                let k = i as u32 + 2;
+                let m = root(z, k);
+                z -= m.pow(k);
+                // The prefix has maximum at most m if x == m, and exactly m otherwise.
+                let p = m.pow(k - 1);
+                match (m + 1).checked_pow(k - 1) {
+                    Some(q) if q <= z => {
+                        let l = z - p;
+                        *x = m - l / (q - p);
+                        z = p + l % (q - p);
+                    }
+                    _ => *x = m,
+                }
+            }
+            *first = z;
+        }
+
+        /// Returns the tuple of length `d` of a Rosenberg-Strong value.
+        ///
+        /// The returned tuple can be encoded with [`encode_rosenberg_strong_tuple`] to get back
+        /// `z`.
+        ///
+        /// See [`decode_rosenberg_strong_tuple_mut`] for a version that writes the tuple to a
+        /// provided slice.
+        ///
+        /// # Panics
+        ///
+        /// Panics in debug mode if `d == 0 && z > 0`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use number_encoding::", $path, "::*;")]
+        /// assert_eq!(decode_rosenberg_strong_tuple(6, 3), &[1, 1, 0]);
+        /// assert_eq!(decode_rosenberg_strong_tuple(3, 3), &[1, 1, 1]);
+        /// assert_eq!(decode_rosenberg_strong_tuple(7, 3), &[1, 0, 0]);
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn decode_rosenberg_strong_tuple(z: Int, d: usize) -> Vec<Int> {
+            let mut xs = vec![0; d];
+            decode_rosenberg_strong_tuple_mut(z, &mut xs);
+            xs
+        }
+
+        #[test]
+        fn rosenberg_strong_tuple_ok() {
+            for d in 0 .. 5u32 {
+                for z in 0 .. if d == 0 { 1 } else { 1000 } {
+                    let xs = decode_rosenberg_strong_tuple(z, d as usize);
+                    assert_eq!(encode_rosenberg_strong_tuple(&xs), Some(z), "z={z} d={d}");
+                    let m = xs.iter().copied().max().unwrap_or(0);
+                    assert!(d == 0 || m.pow(d) <= z && z < (m + 1).pow(d), "z={z} d={d}");
+                }
+            }
+            for d in 1 .. 5 {
+                for z in Int::MAX - 1000 ..= Int::MAX {
+                    let xs = decode_rosenberg_strong_tuple(z, d);
+                    assert_eq!(encode_rosenberg_strong_tuple(&xs), Some(z), "z={z} d={d}");
+                }
+            }
+            assert_eq!(encode_rosenberg_strong_tuple(&[0, 0, Int::MAX]), None);
+        }
+    };
+}
+
+use core::primitive::usize as Int;
+
+pairing!("pairing");
+
+/// Pairing functions with `u128` values
+///
+/// The functions of this module behave like those of the [parent module](super), but with `u128`
+/// values.
+pub mod u128 {
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    use core::primitive::u128 as Int;
+
+    pairing!("pairing::u128");
+}
@@ -0,0 +1,301 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pair number system
+//!
+//! This permits to convert between pairs `(i, j)` with `i < j` (or `i <= j` for the functions
+//! suffixed with `_diagonal`) and values in constant time. This indexes the upper triangle of a
+//! matrix, for example a condensed distance matrix.
+//!
+//! The functions without prefix order pairs in colex order (i.e. by `j` first, then by `i`), which
+//! does not depend on the matrix size. This is the [combinatorial number system] with `k = 2`. The
+//! functions suffixed with `_row_major` order pairs of a matrix of size `n` in row-major order
+//! (i.e. by `i` first, then by `j`).
+//!
+//! [combinatorial number system]: crate::combinadics
+
+use crate::pairing::{triangle, triangle_root};
+
+/// Returns the number of pairs `(i, j)` with `i < j < n`.
+///
+/// # Panics
+///
+/// Panics if the number overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::count;
+/// assert_eq!(count(0), 0);
+/// assert_eq!(count(1), 0);
+/// assert_eq!(count(4), 6);
+/// ```
+pub fn count(n: usize) -> usize {
+    n.checked_sub(1).map_or(0, |w| triangle(w).expect("Overflow"))
+}
+
+/// Returns the number of pairs `(i, j)` with `i <= j < n`.
+///
+/// # Panics
+///
+/// Panics if the number overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::count_diagonal;
+/// assert_eq!(count_diagonal(0), 0);
+/// assert_eq!(count_diagonal(1), 1);
+/// assert_eq!(count_diagonal(4), 10);
+/// ```
+pub fn count_diagonal(n: usize) -> usize {
+    triangle(n).expect("Overflow")
+}
+
+/// Returns the pair of a value in colex order.
+///
+/// The returned pair can be encoded with [`encode`] to get back `r`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::decode;
+/// assert_eq!(decode(0), (0, 1));
+/// assert_eq!(decode(1), (0, 2));
+/// assert_eq!(decode(2), (1, 2));
+/// assert_eq!(decode(3), (0, 3));
+/// ```
+pub fn decode(r: usize) -> (usize, usize) {
+    let (w, i) = triangle_root(r);
+    (i, w + 1)
+}
+
+/// Returns the value of a pair in colex order.
+///
+/// The returned value can be decoded with [`decode`] to get back `(i, j)`.
+///
+/// # Panics
+///
+/// Panics if the value overflows. Panics in debug mode if `i >= j`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::encode;
+/// assert_eq!(encode(0, 1), 0);
+/// assert_eq!(encode(0, 2), 1);
+/// assert_eq!(encode(1, 2), 2);
+/// assert_eq!(encode(0, 3), 3);
+/// ```
+pub fn encode(i: usize, j: usize) -> usize {
+    debug_assert!(i < j, "Failed precondition");
+    triangle(j - 1).and_then(|t| t.checked_add(i)).expect("Overflow")
+}
+
+#[test]
+fn colex_ok() {
+    let mut r = 0;
+    for j in 0 .. 100 {
+        for i in 0 .. j {
+            assert_eq!(encode(i, j), r);
+            assert_eq!(decode(r), (i, j));
+            r += 1;
+        }
+        assert_eq!(count(j + 1), r);
+    }
+    for r in usize::MAX - 1000 ..= usize::MAX {
+        let (i, j) = decode(r);
+        assert_eq!(encode(i, j), r, "r={r}");
+    }
+}
+
+/// Returns the pair of a value in colex order, including the diagonal.
+///
+/// The returned pair can be encoded with [`encode_diagonal`] to get back `r`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::decode_diagonal;
+/// assert_eq!(decode_diagonal(0), (0, 0));
+/// assert_eq!(decode_diagonal(1), (0, 1));
+/// assert_eq!(decode_diagonal(2), (1, 1));
+/// assert_eq!(decode_diagonal(3), (0, 2));
+/// ```
+pub fn decode_diagonal(r: usize) -> (usize, usize) {
+    let (w, i) = triangle_root(r);
+    (i, w)
+}
+
+/// Returns the value of a pair in colex order, including the diagonal.
+///
+/// The returned value can be decoded with [`decode_diagonal`] to get back `(i, j)`.
+///
+/// # Panics
+///
+/// Panics if the value overflows. Panics in debug mode if `i > j`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::encode_diagonal;
+/// assert_eq!(encode_diagonal(0, 0), 0);
+/// assert_eq!(encode_diagonal(0, 1), 1);
+/// assert_eq!(encode_diagonal(1, 1), 2);
+/// assert_eq!(encode_diagonal(0, 2), 3);
+/// ```
+pub fn encode_diagonal(i: usize, j: usize) -> usize {
+    debug_assert!(i <= j, "Failed precondition");
+    triangle(j).and_then(|t| t.checked_add(i)).expect("Overflow")
+}
+
+#[test]
+fn colex_diagonal_ok() {
+    let mut r = 0;
+    for j in 0 .. 100 {
+        for i in 0 ..= j {
+            assert_eq!(encode_diagonal(i, j), r);
+            assert_eq!(decode_diagonal(r), (i, j));
+            r += 1;
+        }
+        assert_eq!(count_diagonal(j + 1), r);
+    }
+    for r in usize::MAX - 1000 ..= usize::MAX {
+        let (i, j) = decode_diagonal(r);
+        assert_eq!(encode_diagonal(i, j), r, "r={r}");
+    }
+}
+
+/// Returns the pair of a value in row-major order in a matrix of size `n`.
+///
+/// The returned pair can be encoded with [`encode_row_major`] to get back `r`.
+///
+/// # Panics
+///
+/// Panics if the number of pairs overflows. Panics in debug mode if `r >= count(n)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::decode_row_major;
+/// assert_eq!(decode_row_major(4, 0), (0, 1));
+/// assert_eq!(decode_row_major(4, 2), (0, 3));
+/// assert_eq!(decode_row_major(4, 3), (1, 2));
+/// assert_eq!(decode_row_major(4, 5), (2, 3));
+/// ```
+pub fn decode_row_major(n: usize, r: usize) -> (usize, usize) {
+    // Row-major order is the reverse of colex order on the reflected pairs.
+    let m = count(n);
+    debug_assert!(r < m, "Failed precondition");
+    let (i, j) = decode(m - 1 - r);
+    (n - 1 - j, n - 1 - i)
+}
+
+/// Returns the value of a pair in row-major order in a matrix of size `n`.
+///
+/// The returned value can be decoded with [`decode_row_major`] to get back `(i, j)`.
+///
+/// # Panics
+///
+/// Panics if the number of pairs overflows. Panics in debug mode if `i >= j` or `j >= n`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::encode_row_major;
+/// assert_eq!(encode_row_major(4, 0, 1), 0);
+/// assert_eq!(encode_row_major(4, 0, 3), 2);
+/// assert_eq!(encode_row_major(4, 1, 2), 3);
+/// assert_eq!(encode_row_major(4, 2, 3), 5);
+/// ```
+pub fn encode_row_major(n: usize, i: usize, j: usize) -> usize {
+    debug_assert!(i < j && j < n, "Failed precondition");
+    count(n) - 1 - encode(n - 1 - j, n - 1 - i)
+}
+
+#[test]
+fn row_major_ok() {
+    for n in 0 .. 30 {
+        let mut r = 0;
+        for i in 0 .. n {
+            for j in i + 1 .. n {
+                assert_eq!(encode_row_major(n, i, j), r);
+                assert_eq!(decode_row_major(n, r), (i, j));
+                r += 1;
+            }
+        }
+        assert_eq!(count(n), r);
+    }
+}
+
+/// Returns the pair of a value in row-major order in a matrix of size `n`, including the diagonal.
+///
+/// The returned pair can be encoded with [`encode_row_major_diagonal`] to get back `r`.
+///
+/// # Panics
+///
+/// Panics if the number of pairs overflows. Panics in debug mode if `r >= count_diagonal(n)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::decode_row_major_diagonal;
+/// assert_eq!(decode_row_major_diagonal(3, 0), (0, 0));
+/// assert_eq!(decode_row_major_diagonal(3, 2), (0, 2));
+/// assert_eq!(decode_row_major_diagonal(3, 3), (1, 1));
+/// assert_eq!(decode_row_major_diagonal(3, 5), (2, 2));
+/// ```
+pub fn decode_row_major_diagonal(n: usize, r: usize) -> (usize, usize) {
+    let m = count_diagonal(n);
+    debug_assert!(r < m, "Failed precondition");
+    let (i, j) = decode_diagonal(m - 1 - r);
+    (n - 1 - j, n - 1 - i)
+}
+
+/// Returns the value of a pair in row-major order in a matrix of size `n`, including the diagonal.
+///
+/// The returned value can be decoded with [`decode_row_major_diagonal`] to get back `(i, j)`.
+///
+/// # Panics
+///
+/// Panics if the number of pairs overflows. Panics in debug mode if `i > j` or `j >= n`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::pairs::encode_row_major_diagonal;
+/// assert_eq!(encode_row_major_diagonal(3, 0, 0), 0);
+/// assert_eq!(encode_row_major_diagonal(3, 0, 2), 2);
+/// assert_eq!(encode_row_major_diagonal(3, 1, 1), 3);
+/// assert_eq!(encode_row_major_diagonal(3, 2, 2), 5);
+/// ```
+pub fn encode_row_major_diagonal(n: usize, i: usize, j: usize) -> usize {
+    debug_assert!(i <= j && j < n, "Failed precondition");
+    count_diagonal(n) - 1 - encode_diagonal(n - 1 - j, n - 1 - i)
+}
+
+#[test]
+fn row_major_diagonal_ok() {
+    for n in 0 .. 30 {
+        let mut r = 0;
+        for i in 0 .. n {
+            for j in i .. n {
+                assert_eq!(encode_row_major_diagonal(n, i, j), r);
+                assert_eq!(decode_row_major_diagonal(n, r), (i, j));
+                r += 1;
+            }
+        }
+        assert_eq!(count_diagonal(n), r);
+    }
+}
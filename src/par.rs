@@ -0,0 +1,264 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parallel enumeration
+//!
+//! This module requires the `rayon` feature. It provides parallel iterators over all
+//! k-combinations, permutations, and multiset permutations. The range of values is split in chunks
+//! (as needed by the [rayon] scheduler). Each chunk decodes its first value, then iterates.
+//!
+//! ```rust
+//! use number_encoding::par;
+//! use rayon::prelude::*;
+//! let count = par::permutations(&[0, 1, 2, 3, 4]).filter(|xs| xs[0] < xs[4]).count();
+//! assert_eq!(count, 60);
+//! ```
+//!
+//! The items are yielded in value order (e.g. when collected).
+//!
+//! The parallel iterators allocate a vector for each item. The `for_each` functions instead lend
+//! each item to a closure, reusing one buffer per chunk:
+//!
+//! ```rust
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use number_encoding::par;
+//! let count = AtomicUsize::new(0);
+//! par::for_each_permutation(&[0, 1, 2, 3, 4], |xs| {
+//!     if xs[0] < xs[4] {
+//!         count.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! });
+//! assert_eq!(count.into_inner(), 60);
+//! ```
+//!
+//! [rayon]: https://docs.rs/rayon
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use rayon::iter::ParallelIterator;
+
+use crate::iter::Lending;
+use crate::{combinadics, factoradics, multinadics};
+
+/// Returns a parallel iterator over chunks of `0 .. count`.
+fn chunks(count: usize) -> impl ParallelIterator<Item = Range<usize>> {
+    rayon::iter::split(0 .. count, |range| {
+        if range.len() < 2 {
+            return (range, None);
+        }
+        let middle = range.start + range.len() / 2;
+        (range.start .. middle, Some(middle .. range.end))
+    })
+}
+
+/// Returns an iterator over the multiset permutations of a non-decreasing slice in `range`.
+fn permutations_in<T: Clone + Ord>(xs: &[T], range: Range<usize>) -> impl Iterator<Item = Vec<T>> {
+    let start = range.start;
+    let mut ys = xs.to_vec();
+    multinadics::decode_mut(&mut ys, start);
+    range.map(move |p| {
+        if p > start {
+            let _ = factoradics::next_permutation(&mut ys);
+        }
+        ys.clone()
+    })
+}
+
+/// Returns a parallel iterator over all k-combinations of a set of `n` elements.
+///
+/// # Panics
+///
+/// Panics if the number of k-combinations overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::par;
+/// use rayon::prelude::*;
+/// let xs: Vec<_> = par::combinations(4, 2).collect();
+/// assert_eq!(xs, [[0, 1], [0, 2], [1, 2], [0, 3], [1, 3], [2, 3]]);
+/// ```
+pub fn combinations(n: usize, k: usize) -> impl ParallelIterator<Item = Vec<usize>> {
+    let count = crate::checked_combination(n, k).expect("Overflow");
+    chunks(count).flat_map_iter(move |range| combinadics::Iter::new_range(k, range).vecs())
+}
+
+#[test]
+fn combinations_ok() {
+    for n in 0 .. 8 {
+        for k in 0 ..= n {
+            let xs: Vec<_> = combinations(n, k).collect();
+            let ys: Vec<_> = combinadics::Iter::new_bounded(n, k).vecs().collect();
+            assert_eq!(xs, ys, "n={n} k={k}");
+        }
+    }
+}
+
+/// Returns a parallel iterator over all permutations of an increasing slice.
+///
+/// # Panics
+///
+/// Panics if the number of permutations overflows.
+///
+/// Panics in debug mode if `xs` is not increasing.
+pub fn permutations<T>(xs: &[T]) -> impl ParallelIterator<Item = Vec<T>> + '_
+where
+    T: Clone + Ord + Send + Sync,
+{
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    let count = crate::checked_factorial(xs.len()).expect("Overflow");
+    chunks(count).flat_map_iter(move |range| permutations_in(xs, range))
+}
+
+#[test]
+fn permutations_ok() {
+    for n in 0 .. 7 {
+        let mut xs: Vec<usize> = (0 .. n).collect();
+        let ys: Vec<_> = permutations(&xs).collect();
+        let zs: Vec<_> = factoradics::Iter::new(&mut xs).vecs().collect();
+        assert_eq!(ys, zs, "n={n}");
+    }
+}
+
+/// Returns a parallel iterator over all multiset permutations of a non-decreasing slice.
+///
+/// # Panics
+///
+/// Panics if the number of multiset permutations overflows.
+///
+/// Panics in debug mode if `xs` is not non-decreasing.
+pub fn multiset_permutations<T>(xs: &[T]) -> impl ParallelIterator<Item = Vec<T>> + '_
+where
+    T: Clone + Ord + Send + Sync,
+{
+    debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+    let count = crate::checked_multinomial(xs).expect("Overflow");
+    chunks(count).flat_map_iter(move |range| permutations_in(xs, range))
+}
+
+#[test]
+fn multiset_permutations_ok() {
+    for xs in [&[][..], &[0], &[0, 0, 1], &[0, 0, 1, 1, 2], &[0, 1, 1, 1, 2, 2, 3]] {
+        let mut xs = xs.to_vec();
+        let ys: Vec<_> = multiset_permutations(&xs).collect();
+        let zs: Vec<_> = multinadics::Iter::new(&mut xs).vecs().collect();
+        assert_eq!(ys, zs, "xs={xs:?}");
+    }
+}
+
+/// Calls a closure on all k-combinations of a set of `n` elements in parallel.
+///
+/// Each chunk iterates with a single buffer, which is lent to `f`.
+///
+/// # Panics
+///
+/// Panics if the number of k-combinations overflows.
+pub fn for_each_combination(n: usize, k: usize, f: impl Fn(&[usize]) + Send + Sync) {
+    let count = crate::checked_combination(n, k).expect("Overflow");
+    chunks(count).for_each(|range| {
+        let mut iter = combinadics::Iter::new_range(k, range);
+        while let Some(xs) = iter.lend() {
+            f(xs);
+        }
+    });
+}
+
+#[test]
+fn for_each_combination_ok() {
+    for n in 0 .. 8 {
+        for k in 0 ..= n {
+            let xs = std::sync::Mutex::new(Vec::new());
+            for_each_combination(n, k, |ys| xs.lock().unwrap().push(ys.to_vec()));
+            let mut xs = xs.into_inner().unwrap();
+            xs.sort_by_key(|xs| combinadics::encode(xs));
+            let ys: Vec<_> = combinadics::Iter::new_bounded(n, k).vecs().collect();
+            assert_eq!(xs, ys, "n={n} k={k}");
+        }
+    }
+}
+
+/// Calls a closure on all permutations of an increasing slice in parallel.
+///
+/// Each chunk iterates with a single buffer, which is lent to `f`.
+///
+/// # Panics
+///
+/// Panics if the number of permutations overflows.
+///
+/// Panics in debug mode if `xs` is not increasing.
+pub fn for_each_permutation<T>(xs: &[T], f: impl Fn(&[T]) + Send + Sync)
+where
+    T: Clone + Ord + Send + Sync,
+{
+    debug_assert!(crate::is_ordered_set(xs), "Failed precondition");
+    let count = crate::checked_factorial(xs.len()).expect("Overflow");
+    chunks(count).for_each(|range| {
+        let mut ys = xs.to_vec();
+        let mut iter = factoradics::Iter::new_at_rank(&mut ys, range.start);
+        for _ in range {
+            f(iter.lend().unwrap());
+        }
+    });
+}
+
+#[test]
+fn for_each_permutation_ok() {
+    for n in 0 .. 7 {
+        let mut xs: Vec<usize> = (0 .. n).collect();
+        let ys = std::sync::Mutex::new(Vec::new());
+        for_each_permutation(&xs, |zs| ys.lock().unwrap().push(zs.to_vec()));
+        let mut ys = ys.into_inner().unwrap();
+        ys.sort();
+        let zs: Vec<_> = factoradics::Iter::new(&mut xs).vecs().collect();
+        assert_eq!(ys, zs, "n={n}");
+    }
+}
+
+/// Calls a closure on all multiset permutations of a non-decreasing slice in parallel.
+///
+/// Each chunk iterates with a single buffer, which is lent to `f`.
+///
+/// # Panics
+///
+/// Panics if the number of multiset permutations overflows.
+///
+/// Panics in debug mode if `xs` is not non-decreasing.
+pub fn for_each_multiset_permutation<T>(xs: &[T], f: impl Fn(&[T]) + Send + Sync)
+where
+    T: Clone + Ord + Send + Sync,
+{
+    debug_assert!(crate::is_ordered_multiset(xs), "Failed precondition");
+    let count = crate::checked_multinomial(xs).expect("Overflow");
+    chunks(count).for_each(|range| {
+        let mut ys = xs.to_vec();
+        let mut iter = multinadics::Iter::new_at_rank(&mut ys, range.start);
+        for _ in range {
+            f(iter.lend().unwrap());
+        }
+    });
+}
+
+#[test]
+fn for_each_multiset_permutation_ok() {
+    for xs in [&[][..], &[0], &[0, 0, 1], &[0, 0, 1, 1, 2], &[0, 1, 1, 1, 2, 2, 3]] {
+        let mut xs = xs.to_vec();
+        let ys = std::sync::Mutex::new(Vec::new());
+        for_each_multiset_permutation(&xs, |zs| ys.lock().unwrap().push(zs.to_vec()));
+        let mut ys = ys.into_inner().unwrap();
+        ys.sort();
+        let zs: Vec<_> = multinadics::Iter::new(&mut xs).vecs().collect();
+        assert_eq!(ys, zs, "xs={xs:?}");
+    }
+}
@@ -0,0 +1,384 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Positive rational number system
+//!
+//! This permits to convert between positive rationals in lowest terms (i.e. `(p, q)` with `p > 0`,
+//! `q > 0`, and `gcd(p, q) == 1`) and values. Rationals are ordered breadth-first in the
+//! [Calkin-Wilf] tree or in the [Stern-Brocot] tree. The value `s` is the node at index `s + 1` of
+//! the tree (i.e. its path from the root is the binary representation of `s + 1` without its
+//! leading one, where zero means left). Both orders start with `1/1`, `1/2`, `2/1`.
+//!
+//! Conversions take time proportional to the number of terms of the [continued fraction] of the
+//! rational, which is logarithmic in the value. This module also provides conversions between
+//! rationals and continued fractions.
+//!
+//! The encoding functions return `None` if the value overflows. The decoding functions never
+//! overflow.
+//!
+//! [Calkin-Wilf]: https://en.wikipedia.org/wiki/Calkin%E2%80%93Wilf_tree
+//! [Stern-Brocot]: https://en.wikipedia.org/wiki/Stern%E2%80%93Brocot_tree
+//! [continued fraction]: https://en.wikipedia.org/wiki/Continued_fraction
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
+
+const BITS: usize = usize::BITS as usize;
+
+/// Calls `f` with the runs of the Calkin-Wilf path of a rational, from the node to the root.
+///
+/// Each run is a direction (true for right) and a positive length. The runs alternate directions.
+fn runs(mut p: usize, mut q: usize, mut f: impl FnMut(bool, usize)) {
+    while p != q {
+        if p > q {
+            let k = (p - 1) / q;
+            f(true, k);
+            p -= k * q;
+        } else {
+            let k = (q - 1) / p;
+            f(false, k);
+            q -= k * p;
+        }
+    }
+}
+
+/// Returns the path of a value: its length and its bits (most significant first).
+fn path(s: usize) -> (usize, usize) {
+    match s.checked_add(1) {
+        None => (BITS, 0),
+        Some(n) => {
+            let len = n.ilog2() as usize;
+            (len, n ^ 1 << len)
+        }
+    }
+}
+
+/// Returns the value of a path, or `None` if it overflows.
+fn unpath(len: usize, bits: usize) -> Option<usize> {
+    match len.cmp(&BITS) {
+        Ordering::Less => Some((1 << len | bits) - 1),
+        Ordering::Equal if bits == 0 => Some(usize::MAX),
+        _ => None,
+    }
+}
+
+/// Returns the Calkin-Wilf rational of a path.
+fn walk(len: usize, bits: usize) -> (usize, usize) {
+    let (mut p, mut q) = (1, 1);
+    for i in (0 .. len).rev() {
+        if bits & 1 << i == 0 {
+            q += p;
+        } else {
+            p += q;
+        }
+    }
+    (p, q)
+}
+
+/// Returns the Calkin-Wilf path of a rational, or `None` if it is too long.
+fn unwalk(p: usize, q: usize) -> Option<(usize, usize)> {
+    debug_assert!(p > 0 && q > 0, "Failed precondition");
+    debug_assert_eq!(crate::greatest_common_divisor(p, q), 1, "Failed precondition");
+    let mut len = 0usize;
+    let mut bits = 0;
+    runs(p, q, |right, k| {
+        if right && len + k <= BITS {
+            bits |= (usize::MAX >> (BITS - k)) << len;
+        }
+        len = len.saturating_add(k);
+    });
+    (len <= BITS).then_some((len, bits))
+}
+
+/// Reverses the `len` least significant bits.
+fn reverse(len: usize, bits: usize) -> usize {
+    match len {
+        0 => 0,
+        _ => bits.reverse_bits() >> (BITS - len),
+    }
+}
+
+#[test]
+fn path_ok() {
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let (len, bits) = path(s);
+        assert_eq!(unpath(len, bits), Some(s), "s={s}");
+        let (p, q) = walk(len, bits);
+        assert_eq!(unwalk(p, q), Some((len, bits)), "s={s}");
+        assert_eq!(reverse(len, reverse(len, bits)), bits, "s={s}");
+    }
+    assert_eq!(unpath(BITS, 1), None);
+    assert_eq!(unpath(BITS + 1, 0), None);
+    assert_eq!(unwalk(1, BITS + 1), Some((BITS, 0)));
+    assert_eq!(unwalk(1, BITS + 2), None);
+}
+
+/// Returns the rational of a value in Calkin-Wilf order.
+///
+/// The returned rational can be encoded with [`encode_calkin_wilf`] to get back `s`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::decode_calkin_wilf;
+/// assert_eq!(decode_calkin_wilf(0), (1, 1));
+/// assert_eq!(decode_calkin_wilf(1), (1, 2));
+/// assert_eq!(decode_calkin_wilf(2), (2, 1));
+/// assert_eq!(decode_calkin_wilf(3), (1, 3));
+/// assert_eq!(decode_calkin_wilf(4), (3, 2));
+/// assert_eq!(decode_calkin_wilf(5), (2, 3));
+/// assert_eq!(decode_calkin_wilf(6), (3, 1));
+/// ```
+pub fn decode_calkin_wilf(s: usize) -> (usize, usize) {
+    let (len, bits) = path(s);
+    walk(len, bits)
+}
+
+/// Returns the value of a rational in Calkin-Wilf order.
+///
+/// The returned value can be decoded with [`decode_calkin_wilf`] to get back `(p, q)`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p == 0`, `q == 0`, or `gcd(p, q) != 1`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::encode_calkin_wilf;
+/// assert_eq!(encode_calkin_wilf(1, 1), Some(0));
+/// assert_eq!(encode_calkin_wilf(3, 2), Some(4));
+/// assert_eq!(encode_calkin_wilf(1, 100), None);
+/// ```
+pub fn encode_calkin_wilf(p: usize, q: usize) -> Option<usize> {
+    let (len, bits) = unwalk(p, q)?;
+    unpath(len, bits)
+}
+
+#[test]
+fn calkin_wilf_ok() {
+    fn fusc(n: usize) -> usize {
+        match n {
+            0 | 1 => n,
+            _ if n.is_multiple_of(2) => fusc(n / 2),
+            _ => fusc(n / 2) + fusc(n / 2 + 1),
+        }
+    }
+    for s in 0 .. 1000 {
+        assert_eq!(decode_calkin_wilf(s), (fusc(s + 1), fusc(s + 2)), "s={s}");
+        assert_eq!(encode_calkin_wilf(fusc(s + 1), fusc(s + 2)), Some(s), "s={s}");
+    }
+    for s in usize::MAX - 1000 ..= usize::MAX {
+        let (p, q) = decode_calkin_wilf(s);
+        assert_eq!(encode_calkin_wilf(p, q), Some(s), "s={s}");
+    }
+    assert_eq!(decode_calkin_wilf(usize::MAX), (1, BITS + 1));
+    assert_eq!(encode_calkin_wilf(1, BITS + 2), None);
+    assert_eq!(encode_calkin_wilf(2, 2 * BITS + 1), None);
+}
+
+/// Returns the rational of a value in Stern-Brocot order.
+///
+/// The returned rational can be encoded with [`encode_stern_brocot`] to get back `s`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::decode_stern_brocot;
+/// assert_eq!(decode_stern_brocot(0), (1, 1));
+/// assert_eq!(decode_stern_brocot(1), (1, 2));
+/// assert_eq!(decode_stern_brocot(2), (2, 1));
+/// assert_eq!(decode_stern_brocot(3), (1, 3));
+/// assert_eq!(decode_stern_brocot(4), (2, 3));
+/// assert_eq!(decode_stern_brocot(5), (3, 2));
+/// assert_eq!(decode_stern_brocot(6), (3, 1));
+/// ```
+pub fn decode_stern_brocot(s: usize) -> (usize, usize) {
+    // The Stern-Brocot path of a rational is the reverse of its Calkin-Wilf path.
+    let (len, bits) = path(s);
+    walk(len, reverse(len, bits))
+}
+
+/// Returns the value of a rational in Stern-Brocot order.
+///
+/// The returned value can be decoded with [`decode_stern_brocot`] to get back `(p, q)`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p == 0`, `q == 0`, or `gcd(p, q) != 1`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::encode_stern_brocot;
+/// assert_eq!(encode_stern_brocot(1, 1), Some(0));
+/// assert_eq!(encode_stern_brocot(3, 2), Some(5));
+/// assert_eq!(encode_stern_brocot(1, 100), None);
+/// ```
+pub fn encode_stern_brocot(p: usize, q: usize) -> Option<usize> {
+    let (len, bits) = unwalk(p, q)?;
+    unpath(len, reverse(len, bits))
+}
+
+#[test]
+fn stern_brocot_ok() {
+    // Returns the rational of a path by mediant descent.
+    fn spec(s: usize) -> (usize, usize) {
+        let (len, bits) = path(s);
+        let (mut a, mut b, mut c, mut d) = (0, 1, 1, 0);
+        for i in (0 .. len).rev() {
+            if bits & 1 << i == 0 {
+                (c, d) = (a + c, b + d);
+            } else {
+                (a, b) = (a + c, b + d);
+            }
+        }
+        (a + c, b + d)
+    }
+    for s in 0 .. 1000 {
+        assert_eq!(decode_stern_brocot(s), spec(s), "s={s}");
+        let (p, q) = spec(s);
+        assert_eq!(encode_stern_brocot(p, q), Some(s), "s={s}");
+    }
+    for s in usize::MAX - 1000 ..= usize::MAX {
+        let (p, q) = decode_stern_brocot(s);
+        assert_eq!(encode_stern_brocot(p, q), Some(s), "s={s}");
+    }
+}
+
+/// Returns the number of terms of the continued fraction of a positive rational.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p == 0` or `q == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::continued_fraction_len;
+/// assert_eq!(continued_fraction_len(1, 1), 1);
+/// assert_eq!(continued_fraction_len(3, 2), 2);
+/// assert_eq!(continued_fraction_len(415, 93), 4);
+/// ```
+pub fn continued_fraction_len(mut p: usize, mut q: usize) -> usize {
+    debug_assert!(p > 0 && q > 0, "Failed precondition");
+    let mut n = 0;
+    while q > 0 {
+        (p, q) = (q, p % q);
+        n += 1;
+    }
+    n
+}
+
+/// Writes the continued fraction of a positive rational to a slice.
+///
+/// The continued fraction is canonical: all terms except the first are positive, and the last
+/// term is at least 2 if there are at least 2 terms. It can be converted with
+/// [`from_continued_fraction`] to get back `(p, q)` in lowest terms.
+///
+/// See [`to_continued_fraction`] for a version that allocates a vector for the continued
+/// fraction.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p == 0`, `q == 0`, or `xs.len() != continued_fraction_len(p, q)`.
+pub fn to_continued_fraction_mut(mut p: usize, mut q: usize, xs: &mut [usize]) {
+    debug_assert_eq!(xs.len(), continued_fraction_len(p, q), "Failed precondition");
+    for x in xs {
+        *x = p / q;
+        (p, q) = (q, p % q);
+    }
+}
+
+/// Returns the continued fraction of a positive rational.
+///
+/// The returned continued fraction can be converted with [`from_continued_fraction`] to get
+/// back `(p, q)` in lowest terms.
+///
+/// See [`to_continued_fraction_mut`] for a version that writes the continued fraction to a
+/// provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `p == 0` or `q == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::to_continued_fraction;
+/// assert_eq!(to_continued_fraction(1, 1), &[1]);
+/// assert_eq!(to_continued_fraction(2, 4), &[0, 2]);
+/// assert_eq!(to_continued_fraction(415, 93), &[4, 2, 6, 7]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_continued_fraction(p: usize, q: usize) -> Vec<usize> {
+    let mut xs = vec![0; continued_fraction_len(p, q)];
+    to_continued_fraction_mut(p, q, &mut xs);
+    xs
+}
+
+/// Returns the rational of a continued fraction.
+///
+/// The returned rational is in lowest terms. It can be converted with [`to_continued_fraction`]
+/// to get back `xs` if `xs` is canonical. Returns `None` if the rational overflows.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is empty or `[0]`, or if one of its terms other than the first is
+/// zero.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::rationals::from_continued_fraction;
+/// assert_eq!(from_continued_fraction(&[1]), Some((1, 1)));
+/// assert_eq!(from_continued_fraction(&[0, 2]), Some((1, 2)));
+/// assert_eq!(from_continued_fraction(&[4, 2, 6, 7]), Some((415, 93)));
+/// assert_eq!(from_continued_fraction(&[0, 1, 1]), Some((1, 2)));
+/// ```
+pub fn from_continued_fraction(xs: &[usize]) -> Option<(usize, usize)> {
+    debug_assert!(!xs.is_empty(), "Failed precondition");
+    debug_assert!(xs.len() > 1 || xs[0] > 0, "Failed precondition");
+    debug_assert!(xs[1 ..].iter().all(|&x| x > 0), "Failed precondition");
+    let (mut p, mut p1) = (1usize, 0usize);
+    let (mut q, mut q1) = (0usize, 1usize);
+    for &x in xs {
+        (p, p1) = (x.checked_mul(p)?.checked_add(p1)?, p);
+        (q, q1) = (x.checked_mul(q)?.checked_add(q1)?, q);
+    }
+    Some((p, q))
+}
+
+#[test]
+fn continued_fraction_ok() {
+    for p in 1 .. 50 {
+        for q in 1 .. 50 {
+            let g = crate::greatest_common_divisor(p, q);
+            let xs = to_continued_fraction(p, q);
+            assert!(xs.len() == 1 || *xs.last().unwrap() >= 2, "p={p} q={q}");
+            assert_eq!(from_continued_fraction(&xs), Some((p / g, q / g)), "p={p} q={q}");
+            if g == 1 {
+                // The Calkin-Wilf path runs are the terms, with the last one decreased.
+                let (len, _) = unwalk(p, q).unwrap();
+                assert_eq!(len, xs.iter().sum::<usize>() - 1, "p={p} q={q}");
+            }
+        }
+    }
+    assert_eq!(to_continued_fraction(usize::MAX, 1), &[usize::MAX]);
+    assert_eq!(from_continued_fraction(&[usize::MAX, 2]), None);
+}
@@ -16,12 +16,28 @@
 //!
 //! This permits to convert between variable-length bit sequences (i.e. `[bool]`) and fixed-length
 //! bit sequences (i.e. `usize`).
+//!
+//! Sequences are ordered by length first, then lexicographically (i.e. shortlex order). This is
+//! bijective base-2 numeration. The functions suffixed with `_base` generalize it to sequences over
+//! an alphabet of `k` letters (i.e. `[u8]` with values less than `k`), which is bijective base-k
+//! numeration. The bit sequence functions are the special case `k = 2`.
+//!
+//! The values of this module are `usize` and thus depend on the platform. The [`u32`](mod@u32),
+//! [`u64`](mod@u64), and [`u128`](mod@u128) modules provide the same functions with fixed-width
+//! values, such that values have the same meaning on all platforms. The [`big`] module provides
+//! them without length limit.
+//!
+//! The [`gray`] module orders bit sequences of the same length by Gray code instead, such that
+//! consecutive values differ by one bit.
 
 #[cfg(feature = "alloc")]
 use alloc::vec;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+pub mod big;
+pub mod gray;
+
 /// Maximum sequence length.
 pub const MAX_LENGTH: usize = usize::BITS as usize - 1;
 
@@ -32,6 +48,8 @@ pub const MAX_SEQUENCE: usize = usize::MAX - 1;
 
 /// Returns the sequence length.
 ///
+/// This is the special case of [`decode_base_len`] with `k = 2`.
+///
 /// # Panics
 ///
 /// Panics in debug mode if `s > MAX_SEQUENCE`.
@@ -69,6 +87,8 @@ fn decode_len_ok() {
 ///
 /// See [`decode`] for a version that allocates a vector for the sequence.
 ///
+/// This is the special case of [`decode_base_mut`] with `k = 2`.
+///
 /// # Panics
 ///
 /// Panics in debug mode if `s > MAX_SEQUENCE` or `xs.len() != decode_len(s)`.
@@ -94,6 +114,8 @@ pub fn decode_mut(s: usize, xs: &mut [bool]) {
 ///
 /// See [`decode_mut`] for a version that writes the sequence to a provided slice.
 ///
+/// This is the special case of [`decode_base`] with `k = 2`.
+///
 /// # Panics
 ///
 /// Panics in debug mode if `s > MAX_SEQUENCE`.
@@ -148,6 +170,8 @@ fn decode_ok() {
 /// assert_eq!(decode(s), xs);
 /// ```
 ///
+/// This is the special case of [`encode_base`] with `k = 2`.
+///
 /// # Panics
 ///
 /// Panics in debug mode if `xs.len() > MAX_LENGTH`.
@@ -191,3 +215,392 @@ fn encode_ok() {
     test(&[true, true, false], 13);
     test(&[true, true, true], 14);
 }
+
+/// Returns the length of the sequence over an alphabet of `k` letters.
+///
+/// # Panics
+///
+/// Panics in debug mode if `k == 0 && s > 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::decode_base_len;
+/// assert_eq!(decode_base_len(0, 3), 0);
+/// assert_eq!(decode_base_len(3, 3), 1);
+/// assert_eq!(decode_base_len(4, 3), 2);
+/// assert_eq!(decode_base_len(12, 3), 2);
+/// assert_eq!(decode_base_len(13, 3), 3);
+/// ```
+pub fn decode_base_len(mut s: usize, k: usize) -> usize {
+    debug_assert!(k > 0 || s == 0, "Failed precondition");
+    if k == 1 {
+        return s;
+    }
+    let mut n = 0;
+    while s > 0 {
+        s = (s - 1) / k;
+        n += 1;
+    }
+    n
+}
+
+#[test]
+fn decode_base_len_ok() {
+    fn test(s: usize, k: usize, n: usize) {
+        assert_eq!(decode_base_len(s, k), n, "s={s} k={k}");
+    }
+    test(0, 0, 0);
+    test(0, 1, 0);
+    test(5, 1, 5);
+    test(usize::MAX, 1, usize::MAX);
+    test(0, 26, 0);
+    test(26, 26, 1);
+    test(27, 26, 2);
+    test(702, 26, 2);
+    test(703, 26, 3);
+    test(usize::MAX, 256, core::mem::size_of::<usize>());
+    test(usize::MAX, 2, usize::BITS as usize);
+    for s in 0 .. 100 {
+        assert_eq!(decode_base_len(s, 2), decode_len(s), "s={s}");
+    }
+}
+
+/// Writes the sequence over an alphabet of `k` letters of a value to a slice.
+///
+/// The written sequence can be encoded with [`encode_base`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::sequences::{decode_base_len, decode_base_mut, encode_base};
+/// # let s = 1000;
+/// # let k = 26;
+/// let n = decode_base_len(s, k);
+/// let mut xs = vec![0; n];
+/// decode_base_mut(s, k, &mut xs);
+/// assert_eq!(encode_base(&xs, k), s);
+/// ```
+///
+/// See [`decode_base`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `k > 256`, `k == 0 && s > 0`, or `xs.len() != decode_base_len(s, k)`.
+pub fn decode_base_mut(mut s: usize, k: usize, xs: &mut [u8]) {
+    debug_assert!(k <= 256, "Failed precondition");
+    debug_assert_eq!(xs.len(), decode_base_len(s, k), "Failed precondition");
+    for x in xs.iter_mut().rev() {
+        s -= 1;
+        *x = (s % k) as u8;
+        s /= k;
+    }
+    debug_assert_eq!(s, 0);
+}
+
+/// Returns the sequence over an alphabet of `k` letters of a value.
+///
+/// The returned sequence can be encoded with [`encode_base`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::sequences::{decode_base, encode_base};
+/// let s = 1000;
+/// let k = 26;
+/// let xs = decode_base(s, k);
+/// assert_eq!(encode_base(&xs, k), s);
+/// ```
+///
+/// See [`decode_base_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `k > 256` or `k == 0 && s > 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::decode_base;
+/// assert_eq!(decode_base(0, 3), &[]);
+/// assert_eq!(decode_base(1, 3), &[0]);
+/// assert_eq!(decode_base(3, 3), &[2]);
+/// assert_eq!(decode_base(4, 3), &[0, 0]);
+/// assert_eq!(decode_base(12, 3), &[2, 2]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_base(s: usize, k: usize) -> Vec<u8> {
+    let n = decode_base_len(s, k);
+    let mut xs = vec![0; n];
+    decode_base_mut(s, k, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_base_ok() {
+    fn test(s: usize, k: usize, xs: &[u8]) {
+        assert_eq!(decode_base(s, k), xs, "s={s} k={k}");
+    }
+    test(0, 0, &[]);
+    test(0, 1, &[]);
+    test(3, 1, &[0, 0, 0]);
+    test(0, 3, &[]);
+    test(1, 3, &[0]);
+    test(2, 3, &[1]);
+    test(3, 3, &[2]);
+    test(4, 3, &[0, 0]);
+    test(5, 3, &[0, 1]);
+    test(7, 3, &[1, 0]);
+    test(12, 3, &[2, 2]);
+    test(13, 3, &[0, 0, 0]);
+    test(usize::MAX, 256, &[254; core::mem::size_of::<usize>()]);
+    for s in 0 .. 100 {
+        let xs: Vec<_> = decode(s).into_iter().map(u8::from).collect();
+        assert_eq!(decode_base(s, 2), xs, "s={s}");
+    }
+}
+
+/// Returns the value of a sequence over an alphabet of `k` letters.
+///
+/// The returned value can be decoded with [`decode_base`] to get back `xs`.
+///
+/// ```rust
+/// # use number_encoding::sequences::{decode_base, encode_base};
+/// # let xs = &[7, 4, 11];
+/// # let k = 26;
+/// let s = encode_base(xs, k);
+/// assert_eq!(decode_base(s, k), xs);
+/// ```
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` contains a value not less than `k` or the value overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::encode_base;
+/// assert_eq!(encode_base(&[], 3), 0);
+/// assert_eq!(encode_base(&[0], 3), 1);
+/// assert_eq!(encode_base(&[2], 3), 3);
+/// assert_eq!(encode_base(&[0, 0], 3), 4);
+/// assert_eq!(encode_base(&[2, 2], 3), 12);
+/// ```
+pub fn encode_base(xs: &[u8], k: usize) -> usize {
+    let mut s = 0;
+    for &x in xs {
+        debug_assert!((x as usize) < k, "Failed precondition");
+        s = k * s + 1 + x as usize;
+    }
+    s
+}
+
+#[test]
+fn encode_base_ok() {
+    fn test(xs: &[u8], k: usize, s: usize) {
+        assert_eq!(encode_base(xs, k), s, "xs={xs:?} k={k}");
+    }
+    test(&[], 0, 0);
+    test(&[], 1, 0);
+    test(&[0, 0, 0], 1, 3);
+    test(&[], 3, 0);
+    test(&[1], 3, 2);
+    test(&[0, 1], 3, 5);
+    test(&[1, 0], 3, 7);
+    test(&[2, 2], 3, 12);
+    test(&[0, 0, 0], 3, 13);
+    test(&[25], 26, 26);
+    test(&[0, 0], 26, 27);
+    test(&[254; core::mem::size_of::<usize>()], 256, usize::MAX);
+    for s in 0 .. 100 {
+        let xs = decode(s);
+        let ys: Vec<_> = xs.iter().map(|&x| x as u8).collect();
+        assert_eq!(encode_base(&ys, 2), encode(&xs), "xs={xs:?}");
+    }
+}
+
+macro_rules! fixed_width {
+    ($name:ident) => {
+        #[doc = concat!("Bit sequence number system with `", stringify!($name), "` values")]
+        ///
+        /// The functions of this module behave like those of the [parent module](super), but with
+        /// values of a fixed width independent of the platform.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("use number_encoding::sequences::", stringify!($name), "::*;")]
+        /// let xs = decode(13);
+        /// assert_eq!(xs, &[true, true, false]);
+        /// assert_eq!(encode(&xs), 13);
+        /// ```
+        pub mod $name {
+            #[cfg(feature = "alloc")]
+            use alloc::vec;
+            #[cfg(feature = "alloc")]
+            use alloc::vec::Vec;
+            use core::primitive::$name as Int;
+
+            /// Maximum sequence length.
+            pub const MAX_LENGTH: usize = Int::BITS as usize - 1;
+
+            /// Maximum sequence value.
+            ///
+            /// Sequences above this value are invalid.
+            pub const MAX_SEQUENCE: Int = Int::MAX - 1;
+
+            /// Returns the sequence length.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `s > MAX_SEQUENCE`.
+            pub fn decode_len(s: Int) -> usize {
+                debug_assert!(s <= MAX_SEQUENCE, "Failed precondition");
+                MAX_LENGTH - (s + 1).leading_zeros() as usize
+            }
+
+            /// Writes the sequence of a value to a slice.
+            ///
+            /// See [`decode`] for a version that allocates a vector for the sequence.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `s > MAX_SEQUENCE` or `xs.len() != decode_len(s)`.
+            pub fn decode_mut(s: Int, xs: &mut [bool]) {
+                debug_assert!(s <= MAX_SEQUENCE, "Failed precondition");
+                let n = decode_len(s);
+                debug_assert_eq!(xs.len(), n, "Failed precondition");
+                for (i, x) in xs.iter_mut().rev().enumerate() {
+                    *x = (s + 1) & 1 << i != 0;
+                }
+            }
+
+            /// Returns the sequence of a value.
+            ///
+            /// See [`decode_mut`] for a version that writes the sequence to a provided slice.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `s > MAX_SEQUENCE`.
+            #[cfg(feature = "alloc")]
+            pub fn decode(s: Int) -> Vec<bool> {
+                let n = decode_len(s);
+                let mut xs = vec![false; n];
+                decode_mut(s, &mut xs);
+                xs
+            }
+
+            /// Returns the value of a sequence.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `xs.len() > MAX_LENGTH`.
+            pub fn encode(xs: &[bool]) -> Int {
+                debug_assert!(xs.len() <= MAX_LENGTH, "Failed precondition");
+                let mut s = 0;
+                for &x in xs {
+                    s = 2 * s + 1 + x as Int;
+                }
+                s
+            }
+
+            /// Returns the length of the sequence over an alphabet of `k` letters.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `k == 1` and the length overflows.
+            ///
+            /// Panics in debug mode if `k == 0 && s > 0`.
+            pub fn decode_base_len(mut s: Int, k: Int) -> usize {
+                debug_assert!(k > 0 || s == 0, "Failed precondition");
+                if k == 1 {
+                    return usize::try_from(s).expect("Overflow");
+                }
+                let mut n = 0;
+                while s > 0 {
+                    s = (s - 1) / k;
+                    n += 1;
+                }
+                n
+            }
+
+            /// Writes the sequence over an alphabet of `k` letters of a value to a slice.
+            ///
+            /// See [`decode_base`] for a version that allocates a vector for the sequence.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `k > 256`, `k == 0 && s > 0`, or
+            /// `xs.len() != decode_base_len(s, k)`.
+            pub fn decode_base_mut(mut s: Int, k: Int, xs: &mut [u8]) {
+                debug_assert!(k <= 256, "Failed precondition");
+                debug_assert_eq!(xs.len(), decode_base_len(s, k), "Failed precondition");
+                for x in xs.iter_mut().rev() {
+                    s -= 1;
+                    *x = (s % k) as u8;
+                    s /= k;
+                }
+                debug_assert_eq!(s, 0);
+            }
+
+            /// Returns the sequence over an alphabet of `k` letters of a value.
+            ///
+            /// See [`decode_base_mut`] for a version that writes the sequence to a provided slice.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `k > 256` or `k == 0 && s > 0`.
+            #[cfg(feature = "alloc")]
+            pub fn decode_base(s: Int, k: Int) -> Vec<u8> {
+                let n = decode_base_len(s, k);
+                let mut xs = vec![0; n];
+                decode_base_mut(s, k, &mut xs);
+                xs
+            }
+
+            /// Returns the value of a sequence over an alphabet of `k` letters.
+            ///
+            /// # Panics
+            ///
+            /// Panics in debug mode if `xs` contains a value not less than `k` or the value
+            /// overflows.
+            pub fn encode_base(xs: &[u8], k: Int) -> Int {
+                let mut s = 0;
+                for &x in xs {
+                    debug_assert!((x as Int) < k, "Failed precondition");
+                    s = k * s + 1 + x as Int;
+                }
+                s
+            }
+
+            #[test]
+            fn bits_ok() {
+                assert_eq!(decode_len(MAX_SEQUENCE), MAX_LENGTH);
+                assert_eq!(encode(&[true; MAX_LENGTH]), MAX_SEQUENCE);
+                assert_eq!(decode(MAX_SEQUENCE), [true; MAX_LENGTH]);
+                assert_eq!(encode(&[false; MAX_LENGTH]), Int::MAX / 2);
+                for s in 0 .. 1000 {
+                    let xs = super::decode(s as usize);
+                    assert_eq!(decode_len(s), xs.len(), "s={s}");
+                    assert_eq!(decode(s), xs, "s={s}");
+                    assert_eq!(encode(&xs), s, "s={s}");
+                }
+            }
+
+            #[test]
+            fn base_ok() {
+                assert_eq!(decode_base_len(Int::MAX, 2), MAX_LENGTH + 1);
+                assert_eq!(decode_base_len(u32::MAX as Int, 1), u32::MAX as usize);
+                assert_eq!(encode_base(&decode_base(Int::MAX, 256), 256), Int::MAX);
+                for k in 1 .. 5 {
+                    for s in 0 .. 1000 {
+                        let xs = super::decode_base(s as usize, k as usize);
+                        assert_eq!(decode_base_len(s, k), xs.len(), "s={s} k={k}");
+                        assert_eq!(decode_base(s, k), xs, "s={s} k={k}");
+                        assert_eq!(encode_base(&xs, k), s, "s={s} k={k}");
+                    }
+                }
+            }
+        }
+    };
+}
+
+fixed_width!(u32);
+fixed_width!(u64);
+fixed_width!(u128);
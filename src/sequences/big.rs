@@ -0,0 +1,554 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Arbitrary-length sequence number system
+//!
+//! The functions of this module behave like those of the [parent module](super), but without
+//! length limit. Values are arbitrary-precision integers represented as little-endian `u64` limbs.
+//! Returned values have no most significant zero limbs (in particular, zero is the empty vector).
+//! Given values may have most significant zero limbs.
+//!
+//! Bit sequences may also be packed in words, most significant bit first: bit `i` of the sequence
+//! is bit `BITS - 1 - i % BITS` of word `i / BITS`. Unused bits of the last word are ignored when
+//! read and cleared when written.
+//!
+//! Byte sequences are sequences over an alphabet of 256 letters (see [`encode_base`]).
+//!
+//! [`encode_base`]: super::encode_base
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+fn bit(s: &[u64], i: usize) -> bool {
+    s.get(i / 64).is_some_and(|&x| x & 1 << (i % 64) != 0)
+}
+
+#[cfg(feature = "alloc")]
+fn set_bit(s: &mut [u64], i: usize) {
+    s[i / 64] |= 1 << (i % 64);
+}
+
+fn bit_len(s: &[u64]) -> usize {
+    match s.iter().rposition(|&x| x != 0) {
+        None => 0,
+        Some(i) => 64 * (i + 1) - s[i].leading_zeros() as usize,
+    }
+}
+
+fn trailing_ones(s: &[u64]) -> usize {
+    match s.iter().position(|&x| x != u64::MAX) {
+        None => 64 * s.len(),
+        Some(i) => 64 * i + s[i].trailing_ones() as usize,
+    }
+}
+
+/// Returns bit `i` of `s + 1` given the number `z` of trailing ones of `s`.
+fn successor_bit(s: &[u64], z: usize, i: usize) -> bool {
+    i == z || (i > z && bit(s, i))
+}
+
+fn byte(s: &[u64], j: usize) -> u8 {
+    s.get(j / 8).map_or(0, |&x| (x >> (8 * (j % 8))) as u8)
+}
+
+#[cfg(feature = "alloc")]
+fn set_byte(s: &mut [u64], j: usize, x: u8) {
+    s[j / 8] |= (x as u64) << (8 * (j % 8));
+}
+
+#[cfg(feature = "alloc")]
+fn normalize(s: &mut Vec<u64>) {
+    while s.last() == Some(&0) {
+        let _ = s.pop();
+    }
+}
+
+/// Returns the sequence length.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::decode_len;
+/// assert_eq!(decode_len(&[]), 0);
+/// assert_eq!(decode_len(&[1]), 1);
+/// assert_eq!(decode_len(&[u64::MAX]), 64);
+/// assert_eq!(decode_len(&[0, 1]), 64);
+/// ```
+pub fn decode_len(s: &[u64]) -> usize {
+    let m = bit_len(s);
+    if trailing_ones(s) >= m {
+        m
+    } else {
+        m - 1
+    }
+}
+
+#[test]
+fn decode_len_ok() {
+    fn test(s: &[u64], n: usize) {
+        assert_eq!(decode_len(s), n, "s={s:?}");
+    }
+    test(&[], 0);
+    test(&[0], 0);
+    test(&[0, 0], 0);
+    test(&[u64::MAX, u64::MAX], 128);
+    test(&[0, 0, 1], 128);
+    test(&[u64::MAX - 1, u64::MAX], 127);
+    for s in 0 .. 100 {
+        assert_eq!(decode_len(&[s]), super::decode_len(s as usize), "s={s}");
+    }
+}
+
+/// Writes the sequence of a value to a slice.
+///
+/// The written sequence can be encoded with [`encode`] to get back `s`.
+///
+/// See [`decode`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s)`.
+pub fn decode_mut(s: &[u64], xs: &mut [bool]) {
+    debug_assert_eq!(xs.len(), decode_len(s), "Failed precondition");
+    let z = trailing_ones(s);
+    for (i, x) in xs.iter_mut().rev().enumerate() {
+        *x = successor_bit(s, z, i);
+    }
+}
+
+/// Returns the sequence of a value.
+///
+/// The returned sequence can be encoded with [`encode`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::sequences::big::{decode, encode};
+/// let s = &[3, 5, 7];
+/// let xs = decode(s);
+/// assert_eq!(encode(&xs), s);
+/// ```
+///
+/// See [`decode_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::decode;
+/// assert_eq!(decode(&[]), &[]);
+/// assert_eq!(decode(&[13]), &[true, true, false]);
+/// assert_eq!(decode(&[u64::MAX]), [false; 64]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode(s: &[u64]) -> Vec<bool> {
+    let mut xs = vec![false; decode_len(s)];
+    decode_mut(s, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_ok() {
+    for s in 0 .. 100 {
+        assert_eq!(decode(&[s]), super::decode(s as usize), "s={s}");
+    }
+    assert_eq!(decode(&[u64::MAX]), [false; 64]);
+    assert_eq!(decode(&[u64::MAX - 1, 0]), [true; 63]);
+    assert_eq!(decode(&[u64::MAX, u64::MAX]), [false; 128]);
+    assert_eq!(decode(&[0, 0, 1]), [[false; 127].as_slice(), &[true]].concat());
+}
+
+/// Returns the value of a sequence.
+///
+/// The returned value can be decoded with [`decode`] to get back `xs`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::encode;
+/// assert_eq!(encode(&[]), &[]);
+/// assert_eq!(encode(&[true, true, false]), &[13]);
+/// assert_eq!(encode(&[false; 64]), &[u64::MAX]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode(xs: &[bool]) -> Vec<u64> {
+    let n = xs.len();
+    let mut s = vec![0; n / 64 + 1];
+    set_bit(&mut s, n);
+    for (i, &x) in xs.iter().rev().enumerate() {
+        if x {
+            set_bit(&mut s, i);
+        }
+    }
+    decrement(&mut s);
+    s
+}
+
+#[cfg(feature = "alloc")]
+fn decrement(s: &mut Vec<u64>) {
+    for x in s.iter_mut() {
+        let (y, borrow) = x.overflowing_sub(1);
+        *x = y;
+        if !borrow {
+            break;
+        }
+    }
+    normalize(s);
+}
+
+#[test]
+fn encode_ok() {
+    for s in 0 .. 100 {
+        let xs = super::decode(s);
+        let r = if s == 0 { vec![] } else { vec![s as u64] };
+        assert_eq!(encode(&xs), r, "xs={xs:?}");
+    }
+    for n in [63, 64, 65, 127, 128, 129, 200] {
+        for xs in [vec![false; n], vec![true; n], (0 .. n).map(|i| i % 3 == 0).collect()] {
+            let s = encode(&xs);
+            assert_eq!(s.last().map(|&x| x != 0), (n > 0).then_some(true), "n={n}");
+            assert_eq!(decode(&s), xs, "n={n}");
+        }
+    }
+}
+
+trait Word: Copy {
+    const BITS: usize;
+    #[cfg(feature = "alloc")]
+    fn get(self, i: usize) -> bool;
+    fn set(&mut self, i: usize);
+}
+
+impl Word for u8 {
+    const BITS: usize = 8;
+    #[cfg(feature = "alloc")]
+    fn get(self, i: usize) -> bool {
+        self & 1 << i != 0
+    }
+    fn set(&mut self, i: usize) {
+        *self |= 1 << i;
+    }
+}
+
+impl Word for u64 {
+    const BITS: usize = 64;
+    #[cfg(feature = "alloc")]
+    fn get(self, i: usize) -> bool {
+        self & 1 << i != 0
+    }
+    fn set(&mut self, i: usize) {
+        *self |= 1 << i;
+    }
+}
+
+fn packed_len<W: Word>(n: usize) -> usize {
+    n.div_ceil(W::BITS)
+}
+
+fn decode_words_mut<W: Word + Default>(s: &[u64], xs: &mut [W]) -> usize {
+    let n = decode_len(s);
+    debug_assert_eq!(xs.len(), packed_len::<W>(n), "Failed precondition");
+    xs.fill(W::default());
+    let z = trailing_ones(s);
+    for i in 0 .. n {
+        if successor_bit(s, z, n - 1 - i) {
+            xs[i / W::BITS].set(W::BITS - 1 - i % W::BITS);
+        }
+    }
+    n
+}
+
+#[cfg(feature = "alloc")]
+fn encode_words<W: Word>(xs: &[W], n: usize) -> Vec<u64> {
+    debug_assert!(n <= W::BITS * xs.len(), "Failed precondition");
+    let mut s = vec![0; n / 64 + 1];
+    set_bit(&mut s, n);
+    for i in 0 .. n {
+        if xs[i / W::BITS].get(W::BITS - 1 - i % W::BITS) {
+            set_bit(&mut s, n - 1 - i);
+        }
+    }
+    decrement(&mut s);
+    s
+}
+
+/// Writes the packed sequence of a value to a slice of words.
+///
+/// Returns the sequence length, which is [`decode_len(s)`](decode_len). The written sequence can be
+/// encoded with [`encode_packed`] to get back `s`.
+///
+/// See [`decode_packed`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s).div_ceil(64)`.
+pub fn decode_packed_mut(s: &[u64], xs: &mut [u64]) -> usize {
+    decode_words_mut(s, xs)
+}
+
+/// Returns the packed sequence of a value and its length.
+///
+/// The returned sequence can be encoded with [`encode_packed`] to get back `s`.
+///
+/// See [`decode_packed_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::decode_packed;
+/// assert_eq!(decode_packed(&[13]), (vec![0xc000000000000000], 3));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_packed(s: &[u64]) -> (Vec<u64>, usize) {
+    let mut xs = vec![0; packed_len::<u64>(decode_len(s))];
+    let n = decode_packed_mut(s, &mut xs);
+    (xs, n)
+}
+
+/// Returns the value of a packed sequence of length `n`.
+///
+/// The returned value can be decoded with [`decode_packed`] to get back `xs` and `n`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `n > 64 * xs.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::encode_packed;
+/// assert_eq!(encode_packed(&[0xc000000000000000], 3), &[13]);
+/// assert_eq!(encode_packed(&[0xc000000000000000], 2), &[6]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_packed(xs: &[u64], n: usize) -> Vec<u64> {
+    encode_words(xs, n)
+}
+
+/// Writes the packed sequence of a value to a slice of bytes.
+///
+/// Returns the sequence length, which is [`decode_len(s)`](decode_len). The written sequence can be
+/// encoded with [`encode_packed_bytes`] to get back `s`.
+///
+/// See [`decode_packed_bytes`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s).div_ceil(8)`.
+pub fn decode_packed_bytes_mut(s: &[u64], xs: &mut [u8]) -> usize {
+    decode_words_mut(s, xs)
+}
+
+/// Returns the packed sequence of a value and its length.
+///
+/// The returned sequence can be encoded with [`encode_packed_bytes`] to get back `s`.
+///
+/// See [`decode_packed_bytes_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::decode_packed_bytes;
+/// assert_eq!(decode_packed_bytes(&[13]), (vec![0xc0], 3));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_packed_bytes(s: &[u64]) -> (Vec<u8>, usize) {
+    let mut xs = vec![0; packed_len::<u8>(decode_len(s))];
+    let n = decode_packed_bytes_mut(s, &mut xs);
+    (xs, n)
+}
+
+/// Returns the value of a packed sequence of length `n`.
+///
+/// The returned value can be decoded with [`decode_packed_bytes`] to get back `xs` and `n`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `n > 8 * xs.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::encode_packed_bytes;
+/// assert_eq!(encode_packed_bytes(&[0xc0], 3), &[13]);
+/// assert_eq!(encode_packed_bytes(&[0xff, 0x00], 9), &[1021]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_packed_bytes(xs: &[u8], n: usize) -> Vec<u64> {
+    encode_words(xs, n)
+}
+
+#[test]
+fn packed_ok() {
+    for n in [0, 1, 7, 8, 9, 63, 64, 65, 130] {
+        for xs in [vec![false; n], vec![true; n], (0 .. n).map(|i| i % 3 == 0).collect()] {
+            let s = encode(&xs);
+            let (words, m) = decode_packed(&s);
+            assert_eq!(m, n);
+            assert_eq!(words.len(), n.div_ceil(64));
+            assert_eq!(encode_packed(&words, n), s, "n={n}");
+            let (bytes, m) = decode_packed_bytes(&s);
+            assert_eq!(m, n);
+            assert_eq!(bytes.len(), n.div_ceil(8));
+            assert_eq!(encode_packed_bytes(&bytes, n), s, "n={n}");
+            for i in 0 .. n {
+                assert_eq!(words[i / 64] & 1 << (63 - i % 64) != 0, xs[i]);
+                assert_eq!(bytes[i / 8] & 1 << (7 - i % 8) != 0, xs[i]);
+            }
+        }
+    }
+    assert_eq!(encode_packed_bytes(&[0xff], 4), encode(&[true; 4]));
+}
+
+/// Returns the byte sequence length.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::decode_len_bytes;
+/// assert_eq!(decode_len_bytes(&[]), 0);
+/// assert_eq!(decode_len_bytes(&[256]), 1);
+/// assert_eq!(decode_len_bytes(&[257]), 2);
+/// ```
+pub fn decode_len_bytes(s: &[u64]) -> usize {
+    let m = bit_len(s).div_ceil(8);
+    // The value is at least the value of the first sequence of length m, namely m zeros, if and
+    // only if it is at least m ones in base 256.
+    for j in (0 .. m).rev() {
+        match byte(s, j) {
+            0 => return m - 1,
+            1 => (),
+            _ => return m,
+        }
+    }
+    m
+}
+
+#[test]
+fn decode_len_bytes_ok() {
+    fn test(s: &[u64], n: usize) {
+        assert_eq!(decode_len_bytes(s), n, "s={s:?}");
+    }
+    test(&[], 0);
+    test(&[0, 0], 0);
+    test(&[0x0101010101010100], 7);
+    test(&[0x0101010101010101], 8);
+    test(&[u64::MAX], 8);
+    test(&[0x0101010101010100, 1], 8);
+    test(&[0x0101010101010101, 1], 9);
+    for s in 0 .. 1000 {
+        assert_eq!(decode_len_bytes(&[s]), super::decode_base_len(s as usize, 256), "s={s}");
+    }
+}
+
+/// Writes the byte sequence of a value to a slice.
+///
+/// The written sequence can be encoded with [`encode_bytes`] to get back `s`.
+///
+/// See [`decode_bytes`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len_bytes(s)`.
+pub fn decode_bytes_mut(s: &[u64], xs: &mut [u8]) {
+    let n = xs.len();
+    debug_assert_eq!(n, decode_len_bytes(s), "Failed precondition");
+    let mut borrow = 0;
+    for (j, x) in xs.iter_mut().rev().enumerate() {
+        let (y, b1) = byte(s, j).overflowing_sub(1);
+        let (y, b2) = y.overflowing_sub(borrow);
+        *x = y;
+        borrow = (b1 || b2) as u8;
+    }
+    debug_assert_eq!(byte(s, n), borrow);
+}
+
+/// Returns the byte sequence of a value.
+///
+/// The returned sequence can be encoded with [`encode_bytes`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::sequences::big::{decode_bytes, encode_bytes};
+/// let s = &[3, 5, 7];
+/// let xs = decode_bytes(s);
+/// assert_eq!(encode_bytes(&xs), s);
+/// ```
+///
+/// See [`decode_bytes_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::decode_bytes;
+/// assert_eq!(decode_bytes(&[]), b"");
+/// assert_eq!(decode_bytes(&[98]), b"a");
+/// assert_eq!(decode_bytes(&[256]), b"\xff");
+/// assert_eq!(decode_bytes(&[257]), b"\0\0");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_bytes(s: &[u64]) -> Vec<u8> {
+    let mut xs = vec![0; decode_len_bytes(s)];
+    decode_bytes_mut(s, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_bytes_ok() {
+    for s in 0 .. 1000 {
+        assert_eq!(decode_bytes(&[s]), super::decode_base(s as usize, 256), "s={s}");
+    }
+    assert_eq!(decode_bytes(&[0x0101010101010101]), [0; 8]);
+    assert_eq!(decode_bytes(&[0x0101010101010100]), [0xff; 7]);
+    assert_eq!(decode_bytes(&[0x0101010101010101, 1]), [0; 9]);
+}
+
+/// Returns the value of a byte sequence.
+///
+/// The returned value can be decoded with [`decode_bytes`] to get back `xs`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::big::encode_bytes;
+/// assert_eq!(encode_bytes(b""), &[]);
+/// assert_eq!(encode_bytes(b"a"), &[98]);
+/// assert_eq!(encode_bytes(&[0; 9]), &[0x0101010101010101, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_bytes(xs: &[u8]) -> Vec<u64> {
+    let n = xs.len();
+    let mut s = vec![0; n / 8 + 1];
+    let mut carry = 0;
+    for (j, &x) in xs.iter().rev().enumerate() {
+        let y = x as u16 + 1 + carry;
+        set_byte(&mut s, j, y as u8);
+        carry = y >> 8;
+    }
+    set_byte(&mut s, n, carry as u8);
+    normalize(&mut s);
+    s
+}
+
+#[test]
+fn encode_bytes_ok() {
+    for s in 0 .. 1000 {
+        let xs = super::decode_base(s, 256);
+        let r = if s == 0 { vec![] } else { vec![s as u64] };
+        assert_eq!(encode_bytes(&xs), r, "xs={xs:?}");
+    }
+    for n in [7, 8, 9, 100] {
+        for xs in [vec![0; n], vec![0xff; n], (0 .. n).map(|i| i as u8).collect()] {
+            assert_eq!(decode_bytes(&encode_bytes(&xs)), xs, "n={n}");
+        }
+    }
+}
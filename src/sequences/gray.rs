@@ -0,0 +1,462 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gray-code bit sequence number system
+//!
+//! Sequences are ordered by length first, then by reflected binary Gray code. Consecutive values
+//! of the same length differ by exactly one bit. The values of a given length are the same as in
+//! the [parent module](super), only their order within the length changes. In particular, the
+//! sequence length is given by [`decode_len`].
+//!
+//! The [`to_plain`] and [`from_plain`] functions convert between the values of this module and
+//! the values of the parent module.
+
+use core::borrow::BorrowMut;
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::{decode_len, MAX_SEQUENCE};
+use crate::iter::{Lending, RankedIter};
+
+/// Returns the value of the first sequence of the same length as `s`.
+fn first(s: usize) -> usize {
+    (1 << decode_len(s)) - 1
+}
+
+fn gray(x: usize) -> usize {
+    x ^ x >> 1
+}
+
+/// Returns the rank of a sequence among the sequences of the same length, modulo `usize::MAX + 1`.
+fn rank(xs: &[bool]) -> usize {
+    let mut parity = false;
+    let mut r = 0usize;
+    for &x in xs {
+        parity ^= x;
+        r = r << 1 | parity as usize;
+    }
+    r
+}
+
+fn gray_inverse(mut x: usize) -> usize {
+    let mut i = 1;
+    while i < usize::BITS {
+        x ^= x >> i;
+        i *= 2;
+    }
+    x
+}
+
+/// Returns the plain value of the sequence with a given Gray value.
+///
+/// The returned value can be converted with [`from_plain`] to get back `s`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `s > MAX_SEQUENCE`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::{self, gray};
+/// let s = 13;
+/// assert_eq!(sequences::decode(gray::to_plain(s)), gray::decode(s));
+/// ```
+pub fn to_plain(s: usize) -> usize {
+    debug_assert!(s <= MAX_SEQUENCE, "Failed precondition");
+    let b = first(s);
+    b + gray(s - b)
+}
+
+#[test]
+fn to_plain_ok() {
+    fn test(s: usize, p: usize) {
+        assert_eq!(to_plain(s), p, "s={s}");
+    }
+    test(0, 0);
+    test(1, 1);
+    test(2, 2);
+    test(3, 3);
+    test(4, 4);
+    test(5, 6);
+    test(6, 5);
+    test(7, 7);
+    test(8, 8);
+    test(9, 10);
+    test(10, 9);
+    test(11, 13);
+    test(12, 14);
+    test(13, 12);
+    test(14, 11);
+    test(MAX_SEQUENCE, usize::MAX / 2 + 1 + usize::MAX / 2 / 2);
+}
+
+/// Returns the Gray value of the sequence with a given plain value.
+///
+/// The returned value can be converted with [`to_plain`] to get back `s`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `s > MAX_SEQUENCE`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::{self, gray};
+/// let s = 13;
+/// assert_eq!(gray::decode(gray::from_plain(s)), sequences::decode(s));
+/// ```
+pub fn from_plain(s: usize) -> usize {
+    debug_assert!(s <= MAX_SEQUENCE, "Failed precondition");
+    let b = first(s);
+    b + gray_inverse(s - b)
+}
+
+#[test]
+fn from_plain_ok() {
+    for s in (0 .. 1000).chain(MAX_SEQUENCE - 1000 ..= MAX_SEQUENCE) {
+        assert_eq!(to_plain(from_plain(s)), s, "s={s}");
+        assert_eq!(from_plain(to_plain(s)), s, "s={s}");
+        assert_eq!(decode_len(from_plain(s)), decode_len(s), "s={s}");
+    }
+}
+
+/// Writes the sequence of a value to a slice.
+///
+/// The written sequence can be encoded with [`encode`] to get back `s`.
+///
+/// See [`decode`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `s > MAX_SEQUENCE` or `xs.len() != decode_len(s)`.
+pub fn decode_mut(s: usize, xs: &mut [bool]) {
+    super::decode_mut(to_plain(s), xs);
+}
+
+/// Returns the sequence of a value.
+///
+/// The returned sequence can be encoded with [`encode`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::sequences::gray::{decode, encode};
+/// let s = 13;
+/// let xs = decode(s);
+/// assert_eq!(encode(&xs), s);
+/// ```
+///
+/// See [`decode_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `s > MAX_SEQUENCE`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::gray::decode;
+/// assert_eq!(decode(0), &[]);
+/// assert_eq!(decode(3), &[false, false]);
+/// assert_eq!(decode(4), &[false, true]);
+/// assert_eq!(decode(5), &[true, true]);
+/// assert_eq!(decode(6), &[true, false]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode(s: usize) -> Vec<bool> {
+    let mut xs = vec![false; decode_len(s)];
+    decode_mut(s, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_ok() {
+    for s in 0 .. 1000usize {
+        let xs = decode(s);
+        assert_eq!(xs.len(), decode_len(s));
+        if decode_len(s + 1) == xs.len() {
+            let ys = decode(s + 1);
+            let d = xs.iter().zip(&ys).filter(|(x, y)| x != y).count();
+            assert_eq!(d, 1, "s={s}");
+        }
+    }
+}
+
+/// Returns the value of a sequence.
+///
+/// The returned value can be decoded with [`decode`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() > MAX_LENGTH`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::sequences::gray::encode;
+/// assert_eq!(encode(&[]), 0);
+/// assert_eq!(encode(&[false, false]), 3);
+/// assert_eq!(encode(&[false, true]), 4);
+/// assert_eq!(encode(&[true, true]), 5);
+/// assert_eq!(encode(&[true, false]), 6);
+/// ```
+pub fn encode(xs: &[bool]) -> usize {
+    from_plain(super::encode(xs))
+}
+
+#[test]
+fn encode_ok() {
+    fn test(xs: &[bool], s: usize) {
+        assert_eq!(encode(xs), s, "xs={xs:?}");
+    }
+    test(&[], 0);
+    test(&[false], 1);
+    test(&[true], 2);
+    test(&[false, false], 3);
+    test(&[false, true], 4);
+    test(&[true, true], 5);
+    test(&[true, false], 6);
+    test(&[false, false, false], 7);
+    test(&[false, false, true], 8);
+    test(&[false, true, true], 9);
+    test(&[false, true, false], 10);
+    test(&[true, true, false], 11);
+    test(&[true, true, true], 12);
+    test(&[true, false, true], 13);
+    test(&[true, false, false], 14);
+}
+
+/// Iterates over all sequences of a given length.
+///
+/// The sequences are iterated in value order, each step flipping exactly one bit. After the last
+/// sequence, the iteration continues with the first sequence, which also differs by one bit:
+///
+/// ```rust
+/// # use number_encoding::sequences::gray::{Iter, encode};
+/// # let n = 4;
+/// let mut iter = Iter::new(n);
+/// for i in 0 .. 1 << n {
+///     assert_eq!(encode(iter.get()), (1 << n) - 1 + i);
+///     iter.advance();
+/// }
+/// assert_eq!(encode(iter.get()), (1 << n) - 1);
+/// ```
+///
+/// # Examples
+///
+/// To iterate over all sequences of length `n`:
+///
+/// ```rust
+/// # use number_encoding::sequences::gray::Iter;
+/// # fn process(xs: &[bool]) {}
+/// # let n = 4;
+/// let mut iter = Iter::new(n);
+/// for _ in 0 .. 1 << n {
+///     process(iter.get());
+///     iter.advance();
+/// }
+/// ```
+///
+/// In a no-std environment, you can pass a buffer of size `N`:
+///
+/// ```rust
+/// # use number_encoding::sequences::gray::Iter;
+/// # const N: usize = 4;
+/// let mut buffer = [false; N];
+/// let mut iter = Iter::new_with_buffer(&mut buffer[..]);
+/// ```
+///
+/// This type implements [`Lending`], which adapts it to standard iterators (see the
+/// [`iter`](crate::iter) module).
+pub struct Iter<T: BorrowMut<[bool]>> {
+    data: T,
+    odd: bool,
+    lent: Option<bool>,
+    rank: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Iter<Vec<bool>> {
+    /// Constructs an iterator.
+    pub fn new(n: usize) -> Iter<Vec<bool>> {
+        Iter::new_with_buffer(vec![false; n])
+    }
+}
+
+impl<T: BorrowMut<[bool]>> Iter<T> {
+    /// Constructs an iterator with a buffer.
+    pub fn new_with_buffer(mut buffer: T) -> Iter<T> {
+        buffer.borrow_mut().fill(false);
+        Iter { data: buffer, odd: false, lent: None, rank: 0 }
+    }
+
+    /// Constructs an iterator starting from a given sequence.
+    pub fn new_from(xs: T) -> Iter<T> {
+        let odd = xs.borrow().iter().filter(|&&x| x).count() % 2 == 1;
+        let rank = rank(xs.borrow());
+        Iter { data: xs, odd, lent: None, rank }
+    }
+
+    /// Returns the current sequence.
+    pub fn get(&self) -> &[bool] {
+        self.data.borrow()
+    }
+
+    /// Returns the rank of the current sequence among the sequences of the same length.
+    ///
+    /// For sequences of `usize::BITS` bits or more, the rank wraps around and only its low bits
+    /// are tracked.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Moves to the sequence of a given rank among the sequences of the same length.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug mode if `rank` is out of range.
+    pub fn seek(&mut self, rank: usize) {
+        let xs = self.data.borrow_mut();
+        let n = xs.len();
+        debug_assert!(n >= usize::BITS as usize || rank >> n == 0, "Failed precondition");
+        let g = gray(rank);
+        for (i, x) in xs.iter_mut().rev().enumerate() {
+            *x = i < usize::BITS as usize && g >> i & 1 == 1;
+        }
+        self.odd = rank % 2 == 1;
+        self.lent = None;
+        self.rank = rank;
+    }
+
+    /// Advances to the next sequence and returns the position of the flipped bit.
+    ///
+    /// Returns `None` (and does nothing) if the sequence is empty.
+    pub fn advance(&mut self) -> Option<usize> {
+        let xs = self.data.borrow_mut();
+        let n = xs.len();
+        let i = if !self.odd {
+            n.checked_sub(1)?
+        } else {
+            match xs.iter().rposition(|&x| x) {
+                Some(0) | None => 0,
+                Some(j) => j - 1,
+            }
+        };
+        xs[i] = !xs[i];
+        self.odd = !self.odd;
+        self.rank = self.rank.wrapping_add(1);
+        if n < usize::BITS as usize {
+            self.rank &= (1 << n) - 1;
+        }
+        Some(i)
+    }
+}
+
+/// Lends the current sequence, then advances before each following call.
+///
+/// This iteration ends after the last sequence of the cycle (i.e. `[true, false, .., false]`).
+impl<T: BorrowMut<[bool]>> Lending for Iter<T> {
+    type Item = bool;
+
+    fn lend(&mut self) -> Option<&[bool]> {
+        match self.lent {
+            None => (),
+            Some(true) => return None,
+            Some(false) => {
+                let _ = self.advance();
+            }
+        }
+        let xs = self.get();
+        let last = xs.first() != Some(&false) && !xs.iter().skip(1).any(|&x| x);
+        self.lent = Some(last);
+        Some(self.get())
+    }
+}
+
+impl<T: BorrowMut<[bool]>> RankedIter for Iter<T> {
+    fn rank(&self) -> usize {
+        self.rank()
+    }
+
+    fn seek(&mut self, rank: usize) {
+        self.seek(rank)
+    }
+}
+
+#[test]
+fn iter_ok() {
+    fn test(n: usize) {
+        let mut iter = Iter::new(n);
+        for i in 0 .. 1 << n {
+            let xs = iter.get().to_vec();
+            assert_eq!(encode(&xs), (1 << n) - 1 + i, "n={n}");
+            let j = iter.advance();
+            let ys = iter.get();
+            if n == 0 {
+                assert_eq!(j, None);
+                continue;
+            }
+            let j = j.unwrap();
+            for k in 0 .. n {
+                assert_eq!(xs[k] != ys[k], k == j, "n={n} i={i}");
+            }
+        }
+        assert_eq!(iter.get(), vec![false; n]);
+    }
+    for n in 0 .. 8 {
+        test(n);
+    }
+    let mut iter = Iter::new_from(vec![true, false, true]);
+    let _ = iter.advance();
+    assert_eq!(iter.get(), &[true, false, false]);
+    let _ = iter.advance();
+    assert_eq!(iter.get(), &[false, false, false]);
+}
+
+#[test]
+fn iter_rank_ok() {
+    let mut iter = Iter::new_from(vec![true, true, false]);
+    for i in 4 .. 14 {
+        assert_eq!(iter.rank(), i % 8);
+        assert_eq!(encode(iter.get()), 7 + i % 8);
+        let _ = iter.advance();
+    }
+    iter.seek(5);
+    assert_eq!(iter.get(), &[true, true, true]);
+    let _ = iter.advance();
+    assert_eq!(iter.get(), &[true, false, true]);
+    assert_eq!(iter.rank(), 6);
+}
+
+#[test]
+fn iter_long_ok() {
+    for n in [64, 65, 200] {
+        let mut iter = Iter::new(n);
+        assert_eq!(iter.advance(), Some(n - 1));
+        assert_eq!(iter.rank(), 1);
+        assert_eq!(iter.advance(), Some(n - 2));
+        assert_eq!(iter.rank(), 2);
+        let mut iter = Iter::new_from(iter.get().to_vec());
+        assert_eq!(iter.rank(), 2);
+        iter.seek(usize::MAX);
+        assert_eq!(iter.rank(), usize::MAX);
+        assert_eq!(rank(iter.get()), usize::MAX);
+        assert_eq!(iter.get().iter().filter(|&&x| x).count(), 1);
+        let _ = iter.advance();
+        assert_eq!(iter.rank(), 0);
+        assert_eq!(rank(iter.get()), 0);
+    }
+}
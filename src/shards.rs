@@ -0,0 +1,219 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shard planner
+//!
+//! This permits to split an enumeration in shards (e.g. to distribute it across machines). A shard
+//! is a range of values with the object of its first value. Each shard can thus be enumerated with
+//! the `Iter` of its number system without decoding each value:
+//!
+//! ```rust
+//! # use number_encoding::{combinadics, shards};
+//! # fn process(xs: &[usize]) {}
+//! # let (n, k) = (10, 4);
+//! for shard in shards::combinations(n, k, 3) {
+//!     let mut iter = combinadics::Iter::new_from(shard.start);
+//!     for _ in shard.range {
+//!         process(iter.get());
+//!         iter.advance();
+//!     }
+//! }
+//! ```
+//!
+//! The shards are balanced: their lengths differ by at most one. The shards of a plan can be
+//! checked with [`is_valid`].
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{combinadics, factoradics, multinadics};
+
+/// A range of values with the object of its first value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard<T> {
+    /// The range of values of the shard.
+    pub range: Range<usize>,
+
+    /// The object of the first value of the shard.
+    pub start: Vec<T>,
+}
+
+/// Returns the balanced split of `0 .. count` in non-empty ranges.
+///
+/// There are `min(shards, count)` ranges. Their lengths differ by at most one.
+///
+/// # Panics
+///
+/// Panics in debug mode if `shards == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::shards::ranges;
+/// assert_eq!(ranges(10, 3).collect::<Vec<_>>(), [0 .. 4, 4 .. 7, 7 .. 10]);
+/// assert_eq!(ranges(2, 3).collect::<Vec<_>>(), [0 .. 1, 1 .. 2]);
+/// assert_eq!(ranges(0, 3).count(), 0);
+/// ```
+pub fn ranges(count: usize, shards: usize) -> impl Iterator<Item = Range<usize>> {
+    debug_assert!(shards > 0, "Failed precondition");
+    let shards = shards.min(count);
+    let q = count.checked_div(shards).unwrap_or(0);
+    let r = count.checked_rem(shards).unwrap_or(0);
+    let start = move |i: usize| i * q + i.min(r);
+    (0 .. shards).map(move |i| start(i) .. start(i + 1))
+}
+
+#[test]
+fn ranges_ok() {
+    for count in 0 .. 50 {
+        for shards in 1 .. 12 {
+            let rs: Vec<_> = ranges(count, shards).collect();
+            assert_eq!(rs.len(), shards.min(count), "count={count} shards={shards}");
+            assert!(is_partition(&rs, count), "count={count} shards={shards}");
+            let min = rs.iter().map(|r| r.len()).min().unwrap_or(1);
+            let max = rs.iter().map(|r| r.len()).max().unwrap_or(1);
+            assert!(0 < min && max <= min + 1, "count={count} shards={shards}");
+        }
+    }
+    let rs: Vec<_> = ranges(usize::MAX, 7).collect();
+    assert!(is_partition(&rs, usize::MAX));
+}
+
+/// Returns whether ranges cover `0 .. count` in order, without gaps or overlaps.
+fn is_partition(ranges: &[Range<usize>], count: usize) -> bool {
+    let mut next = 0;
+    for range in ranges {
+        if range.start != next || range.end < range.start {
+            return false;
+        }
+        next = range.end;
+    }
+    next == count
+}
+
+/// Returns whether shards cover `0 .. count` and start with the right objects.
+///
+/// The shards must be in order, without gaps or overlaps. The first object of each shard must
+/// encode to the start of its range.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::{factoradics, shards};
+/// # let xs = [0, 1, 2, 3];
+/// let plan = shards::permutations(&xs, 5);
+/// assert!(shards::is_valid(&plan, 24, factoradics::encode));
+/// assert!(!shards::is_valid(&plan[1 ..], 24, factoradics::encode));
+/// ```
+pub fn is_valid<T>(shards: &[Shard<T>], count: usize, encode: impl Fn(&[T]) -> usize) -> bool {
+    let ranges: Vec<_> = shards.iter().map(|x| x.range.clone()).collect();
+    is_partition(&ranges, count) && shards.iter().all(|x| encode(&x.start) == x.range.start)
+}
+
+/// Returns the shards of the k-combinations of a set of `n` elements.
+///
+/// See [`ranges`] for how the values are split.
+///
+/// # Panics
+///
+/// Panics if the number of k-combinations overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::shards::combinations;
+/// let plan = combinations(5, 3, 2);
+/// assert_eq!(plan[1].range, 5 .. 10);
+/// assert_eq!(plan[1].start, [0, 2, 4]);
+/// ```
+pub fn combinations(n: usize, k: usize, shards: usize) -> Vec<Shard<usize>> {
+    let count = crate::checked_combination(n, k).expect("Overflow");
+    ranges(count, shards)
+        .map(|range| Shard { start: combinadics::decode(range.start, k), range })
+        .collect()
+}
+
+#[test]
+fn combinations_ok() {
+    for n in 0 .. 8 {
+        for k in 0 ..= n + 1 {
+            for shards in 1 .. 6 {
+                let plan = combinations(n, k, shards);
+                let count = crate::combination(n, k);
+                assert!(is_valid(&plan, count, combinadics::encode), "n={n} k={k}");
+            }
+        }
+    }
+}
+
+/// Returns the shards of the permutations of an increasing slice.
+///
+/// See [`ranges`] for how the values are split.
+///
+/// # Panics
+///
+/// Panics if the number of permutations overflows.
+///
+/// Panics in debug mode if `xs` is not increasing.
+pub fn permutations<T: Clone + Ord>(xs: &[T], shards: usize) -> Vec<Shard<T>> {
+    let count = crate::checked_factorial(xs.len()).expect("Overflow");
+    ranges(count, shards)
+        .map(|range| Shard { start: factoradics::decode(xs, range.start), range })
+        .collect()
+}
+
+#[test]
+fn permutations_ok() {
+    for n in 0 .. 6 {
+        let xs: Vec<usize> = (0 .. n).collect();
+        for shards in 1 .. 30 {
+            let plan = permutations(&xs, shards);
+            assert!(is_valid(&plan, crate::factorial(n), factoradics::encode), "n={n}");
+        }
+    }
+}
+
+/// Returns the shards of the multiset permutations of a non-decreasing slice.
+///
+/// See [`ranges`] for how the values are split.
+///
+/// # Panics
+///
+/// Panics if the number of multiset permutations overflows.
+///
+/// Panics in debug mode if `xs` is not non-decreasing.
+pub fn multiset_permutations<T: Clone + Ord>(xs: &[T], shards: usize) -> Vec<Shard<T>> {
+    let count = crate::checked_multinomial(xs).expect("Overflow");
+    ranges(count, shards)
+        .map(|range| Shard { start: multinadics::decode(xs, range.start), range })
+        .collect()
+}
+
+#[test]
+fn multiset_permutations_ok() {
+    for xs in [&[][..], &[0], &[0, 0, 1], &[0, 0, 1, 1, 2], &[0, 1, 1, 1, 2, 2, 3]] {
+        for shards in 1 .. 10 {
+            let plan = multiset_permutations(xs, shards);
+            let count = crate::multinomial(xs);
+            assert!(is_valid(&plan, count, multinadics::encode), "xs={xs:?}");
+            for shard in plan {
+                let mut ys = shard.start.clone();
+                let mut iter = multinadics::Iter::new_from(&mut ys);
+                for p in shard.range {
+                    assert_eq!(multinadics::encode(iter.next().unwrap()), p, "xs={xs:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,419 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ordered tree number system
+//!
+//! This permits to convert between values and finite rooted ordered trees, or s-expressions over a
+//! finite alphabet of atoms.
+//!
+//! A tree is represented by the number of children of its nodes in pre-order (also known as its
+//! [Łukasiewicz word]). For example `[2, 0, 1, 0]` is a root with a leaf as first child and a node
+//! with a leaf as second child.
+//!
+//! An s-expression over `atoms` atoms is either an atom or a list of s-expressions. It is
+//! represented by its tokens in pre-order, where `x < atoms` is the atom `x` and `atoms + k` is a
+//! list of `k` s-expressions. For example with 2 atoms, `[4, 0, 3, 1]` is `(a (b))`. Trees are
+//! s-expressions without atoms.
+//!
+//! Values are ordered by number of nodes (or tokens) first, then lexicographically. In particular,
+//! smaller trees have smaller values.
+//!
+//! The encoding functions return `None` if the value overflows. The decoding functions never
+//! overflow.
+//!
+//! [Łukasiewicz word]: https://en.wikipedia.org/wiki/%C5%81ukasiewicz_word
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Returns the number of sequences of `d` s-expressions with `r` tokens in total.
+///
+/// Each leaf can be one of `w` tokens (the atoms and the empty list). Returns `None` if the number
+/// overflows.
+fn forests(w: usize, r: usize, d: usize) -> Option<usize> {
+    if d >= r {
+        let n = if d == r { (w as u128).checked_pow(u32::try_from(d).ok()?)? } else { 0 };
+        return usize::try_from(n).ok();
+    }
+    if d == 0 {
+        return Some(0);
+    }
+    // This is the Lagrange inversion of T = x (w + T / (1 - T)).
+    let mut n = 0u128;
+    for j in 1 ..= r - d {
+        let mut t = (w as u128).checked_pow(u32::try_from(r - j).ok()?)?;
+        t = t.checked_mul(crate::checked_combination(r, j)? as u128)?;
+        t = t.checked_mul(crate::checked_combination(r - d - 1, j - 1)? as u128)?;
+        n = n.checked_add(t)?;
+    }
+    usize::try_from(n.checked_mul(d as u128)? / r as u128).ok()
+}
+
+/// Returns the number of children of a token.
+fn arity(atoms: usize, x: usize) -> usize {
+    x.saturating_sub(atoms)
+}
+
+/// Returns whether a slice represents an s-expression.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::is_sexp;
+/// assert!(is_sexp(&[0], 2));
+/// assert!(is_sexp(&[2], 2));
+/// assert!(is_sexp(&[4, 0, 3, 1], 2));
+/// assert!(!is_sexp(&[], 2));
+/// assert!(!is_sexp(&[3], 2));
+/// assert!(!is_sexp(&[0, 1], 2));
+/// ```
+pub fn is_sexp(xs: &[usize], atoms: usize) -> bool {
+    let mut d = 1usize;
+    for &x in xs {
+        if d == 0 {
+            return false;
+        }
+        d = match (d - 1).checked_add(arity(atoms, x)) {
+            Some(d) => d,
+            None => return false,
+        };
+    }
+    d == 0
+}
+
+/// Returns the number of s-expressions with `n` tokens.
+///
+/// Returns `None` if the number overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::count_sexp;
+/// assert_eq!(count_sexp(0, 2), Some(0));
+/// assert_eq!(count_sexp(1, 2), Some(3));
+/// assert_eq!(count_sexp(2, 2), Some(3));
+/// assert_eq!(count_sexp(3, 2), Some(12));
+/// ```
+pub fn count_sexp(n: usize, atoms: usize) -> Option<usize> {
+    forests(atoms.checked_add(1)?, n, 1)
+}
+
+#[test]
+fn count_sexp_ok() {
+    fn spec(n: usize, atoms: usize) -> usize {
+        let mut xs = vec![0; n];
+        let mut r = 0;
+        loop {
+            r += is_sexp(&xs, atoms) as usize;
+            let Some(i) = xs.iter().position(|&x| x < atoms + n - 1) else { return r };
+            xs[.. i].fill(0);
+            xs[i] += 1;
+        }
+    }
+    for atoms in 0 .. 4 {
+        for n in 1 .. 7 {
+            assert_eq!(count_sexp(n, atoms), Some(spec(n, atoms)), "n={n} atoms={atoms}");
+        }
+    }
+    assert_eq!(count_sexp(36, 0), Some(3116285494907301262));
+    assert_eq!(count_sexp(37, 0), Some(11959798385860453492));
+    assert_eq!(count_sexp(38, 0), None);
+}
+
+/// Returns the number of tokens of the s-expression of a value.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::decode_sexp_len;
+/// assert_eq!(decode_sexp_len(0, 2), 1);
+/// assert_eq!(decode_sexp_len(2, 2), 1);
+/// assert_eq!(decode_sexp_len(3, 2), 2);
+/// assert_eq!(decode_sexp_len(6, 2), 3);
+/// ```
+pub fn decode_sexp_len(mut s: usize, atoms: usize) -> usize {
+    let mut n = 1;
+    loop {
+        match count_sexp(n, atoms) {
+            Some(c) if c <= s => s -= c,
+            _ => return n,
+        }
+        n += 1;
+    }
+}
+
+/// Writes the s-expression of a value to a slice.
+///
+/// The written s-expression can be encoded with [`encode_sexp`] to get back `s`.
+///
+/// See [`decode_sexp`] for a version that allocates a vector for the s-expression.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_sexp_len(s, atoms)`.
+pub fn decode_sexp_mut(mut s: usize, atoms: usize, xs: &mut [usize]) {
+    let n = decode_sexp_len(s, atoms);
+    debug_assert_eq!(xs.len(), n, "Failed precondition");
+    for m in 1 .. n {
+        s -= count_sexp(m, atoms).unwrap();
+    }
+    let Some(w) = atoms.checked_add(1) else {
+        // Only the single tokens are representable: the atoms and the empty list.
+        xs[0] = s;
+        return;
+    };
+    let mut d = 1;
+    for (i, x) in xs.iter_mut().enumerate() {
+        let r = n - i - 1;
+        let c = forests(w, r, d - 1);
+        match c.and_then(|c| c.checked_mul(atoms)) {
+            Some(t) if t <= s => s -= t,
+            _ => {
+                // An overflowing count is larger than any value.
+                if let Some(c) = c {
+                    *x = s / c;
+                    s %= c;
+                }
+                d -= 1;
+                continue;
+            }
+        }
+        let mut k = 0;
+        loop {
+            match forests(w, r, d - 1 + k) {
+                Some(c) if c <= s => s -= c,
+                _ => break,
+            }
+            k += 1;
+        }
+        *x = atoms + k;
+        d = d - 1 + k;
+    }
+}
+
+/// Returns the s-expression of a value.
+///
+/// The returned s-expression can be encoded with [`encode_sexp`] to get back `s`.
+///
+/// See [`decode_sexp_mut`] for a version that writes the s-expression to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::decode_sexp;
+/// assert_eq!(decode_sexp(0, 2), &[0]);
+/// assert_eq!(decode_sexp(1, 2), &[1]);
+/// assert_eq!(decode_sexp(2, 2), &[2]);
+/// assert_eq!(decode_sexp(3, 2), &[3, 0]);
+/// assert_eq!(decode_sexp(4, 2), &[3, 1]);
+/// assert_eq!(decode_sexp(5, 2), &[3, 2]);
+/// assert_eq!(decode_sexp(6, 2), &[3, 3, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_sexp(s: usize, atoms: usize) -> Vec<usize> {
+    let mut xs = vec![0; decode_sexp_len(s, atoms)];
+    decode_sexp_mut(s, atoms, &mut xs);
+    xs
+}
+
+/// Returns the value of an s-expression.
+///
+/// The returned value can be decoded with [`decode_sexp`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not an s-expression.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::encode_sexp;
+/// assert_eq!(encode_sexp(&[0], 2), Some(0));
+/// assert_eq!(encode_sexp(&[3, 2], 2), Some(5));
+/// assert_eq!(encode_sexp(&[4, 0, 3, 1], 2), Some(31));
+/// ```
+pub fn encode_sexp(xs: &[usize], atoms: usize) -> Option<usize> {
+    debug_assert!(is_sexp(xs, atoms), "Failed precondition");
+    let n = xs.len();
+    let Some(w) = atoms.checked_add(1) else {
+        // Only the single tokens are representable: the atoms and the empty list.
+        return Some(xs[0]);
+    };
+    let mut s = 0usize;
+    for m in 1 .. n {
+        s = s.checked_add(count_sexp(m, atoms)?)?;
+    }
+    let mut d = 1;
+    for (i, &x) in xs.iter().enumerate() {
+        let r = n - i - 1;
+        let c = forests(w, r, d - 1)?;
+        if x < atoms {
+            s = s.checked_add(c.checked_mul(x)?)?;
+            d -= 1;
+            continue;
+        }
+        s = s.checked_add(c.checked_mul(atoms)?)?;
+        let k = x - atoms;
+        for j in 0 .. k {
+            s = s.checked_add(forests(w, r, d - 1 + j)?)?;
+        }
+        d = d - 1 + k;
+    }
+    Some(s)
+}
+
+#[test]
+fn sexp_ok() {
+    fn words(n: usize, atoms: usize) -> Vec<Vec<usize>> {
+        let mut r = Vec::new();
+        let mut xs = vec![0; n];
+        loop {
+            if is_sexp(&xs, atoms) {
+                r.push(xs.clone());
+            }
+            let Some(i) = xs.iter().rposition(|&x| x < atoms + n - 1) else { return r };
+            xs[i + 1 ..].fill(0);
+            xs[i] += 1;
+        }
+    }
+    for atoms in 0 .. 4 {
+        let mut s = 0;
+        for n in 1 .. 7 {
+            for xs in words(n, atoms) {
+                assert_eq!(decode_sexp(s, atoms), xs, "s={s} atoms={atoms}");
+                assert_eq!(encode_sexp(&xs, atoms), Some(s), "s={s} atoms={atoms}");
+                s += 1;
+            }
+        }
+    }
+    for atoms in [0, 1, 2, 100, usize::MAX - 1, usize::MAX] {
+        for s in usize::MAX - 100 ..= usize::MAX {
+            let xs = decode_sexp(s, atoms);
+            assert!(is_sexp(&xs, atoms), "s={s} atoms={atoms}");
+            assert_eq!(encode_sexp(&xs, atoms), Some(s), "s={s} atoms={atoms}");
+        }
+    }
+    assert_eq!(decode_sexp(0, usize::MAX), &[0]);
+    let mut xs = vec![1; 40];
+    xs[39] = 0;
+    assert_eq!(encode_sexp(&xs, 0), None);
+}
+
+/// Returns whether a slice represents a tree.
+///
+/// This is the same as [`is_sexp`] without atoms.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::is_tree;
+/// assert!(is_tree(&[0]));
+/// assert!(is_tree(&[2, 0, 1, 0]));
+/// assert!(!is_tree(&[]));
+/// assert!(!is_tree(&[1]));
+/// assert!(!is_tree(&[0, 0]));
+/// ```
+pub fn is_tree(xs: &[usize]) -> bool {
+    is_sexp(xs, 0)
+}
+
+/// Returns the number of trees with `n` nodes.
+///
+/// This is the Catalan number of `n - 1`. Returns `None` if the number overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::count;
+/// assert_eq!(count(1), Some(1));
+/// assert_eq!(count(2), Some(1));
+/// assert_eq!(count(3), Some(2));
+/// assert_eq!(count(4), Some(5));
+/// assert_eq!(count(5), Some(14));
+/// ```
+pub fn count(n: usize) -> Option<usize> {
+    count_sexp(n, 0)
+}
+
+/// Returns the number of nodes of the tree of a value.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::decode_len;
+/// assert_eq!(decode_len(0), 1);
+/// assert_eq!(decode_len(1), 2);
+/// assert_eq!(decode_len(2), 3);
+/// assert_eq!(decode_len(4), 4);
+/// ```
+pub fn decode_len(s: usize) -> usize {
+    decode_sexp_len(s, 0)
+}
+
+/// Writes the tree of a value to a slice.
+///
+/// The written tree can be encoded with [`encode`] to get back `s`.
+///
+/// See [`decode`] for a version that allocates a vector for the tree.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s)`.
+pub fn decode_mut(s: usize, xs: &mut [usize]) {
+    decode_sexp_mut(s, 0, xs);
+}
+
+/// Returns the tree of a value.
+///
+/// The returned tree can be encoded with [`encode`] to get back `s`.
+///
+/// See [`decode_mut`] for a version that writes the tree to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::decode;
+/// assert_eq!(decode(0), &[0]);
+/// assert_eq!(decode(1), &[1, 0]);
+/// assert_eq!(decode(2), &[1, 1, 0]);
+/// assert_eq!(decode(3), &[2, 0, 0]);
+/// assert_eq!(decode(4), &[1, 1, 1, 0]);
+/// assert_eq!(decode(5), &[1, 2, 0, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode(s: usize) -> Vec<usize> {
+    decode_sexp(s, 0)
+}
+
+/// Returns the value of a tree.
+///
+/// The returned value can be decoded with [`decode`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs` is not a tree.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::trees::encode;
+/// assert_eq!(encode(&[0]), Some(0));
+/// assert_eq!(encode(&[2, 0, 0]), Some(3));
+/// assert_eq!(encode(&[2, 0, 1, 0]), Some(6));
+/// ```
+pub fn encode(xs: &[usize]) -> Option<usize> {
+    encode_sexp(xs, 0)
+}
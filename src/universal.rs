@@ -0,0 +1,540 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Universal codes
+//!
+//! This permits to write positive integers (i.e. `usize` values at least 1) to bit buffers with
+//! prefix-free codes and read them back. The supported codes are [Elias gamma], [Elias delta],
+//! [Elias omega], and [Fibonacci] codes. To write non-negative integers, write them plus one.
+//!
+//! Bits are stored in bytes, most significant bit first: bit `i` of the buffer is bit `7 - i % 8`
+//! of byte `i / 8`.
+//!
+//! The Elias gamma code of `s + 1` is the length of the [sequence](crate::sequences) of `s` in
+//! unary (i.e. that many zeros followed by a one), followed by that sequence:
+//!
+//! ```rust
+//! # use number_encoding::sequences;
+//! # use number_encoding::universal::{Reader, Writer};
+//! # let s = 13;
+//! let xs = sequences::decode(s);
+//! let mut buffer = [0u8; 4];
+//! let mut writer = Writer::new(&mut buffer[..]);
+//! writer.write_gamma(s + 1);
+//! let n = writer.position();
+//! let mut reader = Reader::new(&buffer[..]);
+//! for _ in 0 .. xs.len() {
+//!     assert_eq!(reader.read_bit(), Some(false));
+//! }
+//! assert_eq!(reader.read_bit(), Some(true));
+//! for &x in &xs {
+//!     assert_eq!(reader.read_bit(), Some(x));
+//! }
+//! assert_eq!(reader.position(), n);
+//! ```
+//!
+//! [Elias gamma]: https://en.wikipedia.org/wiki/Elias_gamma_coding
+//! [Elias delta]: https://en.wikipedia.org/wiki/Elias_delta_coding
+//! [Elias omega]: https://en.wikipedia.org/wiki/Elias_omega_coding
+//! [Fibonacci]: https://en.wikipedia.org/wiki/Fibonacci_coding
+
+use core::borrow::{Borrow, BorrowMut};
+
+/// Returns the number of bits of `x` after its leading one.
+fn log2(x: usize) -> usize {
+    (usize::BITS - 1 - x.leading_zeros()) as usize
+}
+
+/// Returns the length of the Elias gamma code of `x`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `x == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::universal::gamma_len;
+/// assert_eq!(gamma_len(1), 1);
+/// assert_eq!(gamma_len(2), 3);
+/// assert_eq!(gamma_len(4), 5);
+/// ```
+pub fn gamma_len(x: usize) -> usize {
+    debug_assert!(x > 0, "Failed precondition");
+    2 * log2(x) + 1
+}
+
+/// Returns the length of the Elias delta code of `x`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `x == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::universal::delta_len;
+/// assert_eq!(delta_len(1), 1);
+/// assert_eq!(delta_len(2), 4);
+/// assert_eq!(delta_len(17), 9);
+/// ```
+pub fn delta_len(x: usize) -> usize {
+    debug_assert!(x > 0, "Failed precondition");
+    let n = log2(x);
+    gamma_len(n + 1) + n
+}
+
+/// Returns the length of the Elias omega code of `x`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `x == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::universal::omega_len;
+/// assert_eq!(omega_len(1), 1);
+/// assert_eq!(omega_len(2), 3);
+/// assert_eq!(omega_len(16), 11);
+/// ```
+pub fn omega_len(mut x: usize) -> usize {
+    debug_assert!(x > 0, "Failed precondition");
+    let mut n = 1;
+    while x > 1 {
+        x = log2(x);
+        n += x + 1;
+    }
+    n
+}
+
+/// Returns the length of the Fibonacci code of `x`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `x == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::universal::fibonacci_len;
+/// assert_eq!(fibonacci_len(1), 2);
+/// assert_eq!(fibonacci_len(4), 4);
+/// assert_eq!(fibonacci_len(11), 6);
+/// ```
+pub fn fibonacci_len(x: usize) -> usize {
+    debug_assert!(x > 0, "Failed precondition");
+    let (mut a, mut b) = (1usize, 2usize);
+    let mut n = 2;
+    while b <= x {
+        (a, b) = (b, a.saturating_add(b));
+        n += 1;
+        if b == usize::MAX {
+            break;
+        }
+    }
+    n
+}
+
+/// Writes bits to a buffer.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::universal::Writer;
+/// let mut buffer = [0u8; 2];
+/// let mut writer = Writer::new(&mut buffer[..]);
+/// writer.write_gamma(4);
+/// writer.write_fibonacci(4);
+/// assert_eq!(writer.position(), 9);
+/// assert_eq!(buffer, [0b00100101, 0b10000000]);
+/// ```
+pub struct Writer<T: BorrowMut<[u8]>> {
+    data: T,
+    position: usize,
+}
+
+impl<T: BorrowMut<[u8]>> Writer<T> {
+    /// Constructs a writer starting at the beginning of a buffer.
+    pub fn new(buffer: T) -> Writer<T> {
+        Writer { data: buffer, position: 0 }
+    }
+
+    /// Returns the number of written bits.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the buffer.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Writes a bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is full.
+    pub fn write_bit(&mut self, x: bool) {
+        let byte = &mut self.data.borrow_mut()[self.position / 8];
+        let mask = 1 << (7 - self.position % 8);
+        if x {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+        self.position += 1;
+    }
+
+    /// Writes a sequence of bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small.
+    pub fn write_bits(&mut self, xs: &[bool]) {
+        for &x in xs {
+            self.write_bit(x);
+        }
+    }
+
+    /// Writes the `n` least significant bits of `x`, most significant first.
+    fn write_int(&mut self, x: usize, n: usize) {
+        for i in (0 .. n).rev() {
+            self.write_bit(x & 1 << i != 0);
+        }
+    }
+
+    /// Writes the Elias gamma code of a positive integer.
+    ///
+    /// This writes [`gamma_len(x)`](gamma_len) bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small. Panics in debug mode if `x == 0`.
+    pub fn write_gamma(&mut self, x: usize) {
+        debug_assert!(x > 0, "Failed precondition");
+        let n = log2(x);
+        self.write_int(0, n);
+        self.write_int(x, n + 1);
+    }
+
+    /// Writes the Elias delta code of a positive integer.
+    ///
+    /// This writes [`delta_len(x)`](delta_len) bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small. Panics in debug mode if `x == 0`.
+    pub fn write_delta(&mut self, x: usize) {
+        debug_assert!(x > 0, "Failed precondition");
+        let n = log2(x);
+        self.write_gamma(n + 1);
+        self.write_int(x, n);
+    }
+
+    /// Writes the Elias omega code of a positive integer.
+    ///
+    /// This writes [`omega_len(x)`](omega_len) bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small. Panics in debug mode if `x == 0`.
+    pub fn write_omega(&mut self, mut x: usize) {
+        debug_assert!(x > 0, "Failed precondition");
+        // The groups are computed from last to first. There are at most 5 groups for 64 bits.
+        let mut groups = [0; usize::BITS.ilog2() as usize];
+        let mut k = 0;
+        while x > 1 {
+            groups[k] = x;
+            k += 1;
+            x = log2(x);
+        }
+        for &x in groups[.. k].iter().rev() {
+            self.write_int(x, log2(x) + 1);
+        }
+        self.write_bit(false);
+    }
+
+    /// Writes the Fibonacci code of a positive integer.
+    ///
+    /// This writes [`fibonacci_len(x)`](fibonacci_len) bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small. Panics in debug mode if `x == 0`.
+    pub fn write_fibonacci(&mut self, mut x: usize) {
+        debug_assert!(x > 0, "Failed precondition");
+        // Find the largest Fibonacci number at most x, then compute the Zeckendorf representation
+        // greedily from the most significant digit. Bit i of the mask uses F(i + 2).
+        let (mut a, mut b) = (1usize, 2usize);
+        let mut n = 0;
+        while b <= x {
+            match a.checked_add(b) {
+                Some(c) => (a, b) = (b, c),
+                None => {
+                    (a, b) = (b, a.wrapping_add(b));
+                    n += 1;
+                    break;
+                }
+            }
+            n += 1;
+        }
+        let mut mask = 0u128;
+        for i in (0 ..= n).rev() {
+            if a <= x {
+                x -= a;
+                mask |= 1 << i;
+            }
+            (a, b) = (b.wrapping_sub(a), a);
+        }
+        debug_assert_eq!(x, 0);
+        for i in 0 ..= n {
+            self.write_bit(mask & 1 << i != 0);
+        }
+        self.write_bit(true);
+    }
+}
+
+/// Reads bits from a buffer.
+///
+/// The reading functions return `None` if the buffer ends before the value, or if the value
+/// overflows `usize`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::universal::Reader;
+/// let buffer = [0b00100101, 0b10000000];
+/// let mut reader = Reader::new(&buffer[..]);
+/// assert_eq!(reader.read_gamma(), Some(4));
+/// assert_eq!(reader.read_fibonacci(), Some(4));
+/// assert_eq!(reader.position(), 9);
+/// ```
+pub struct Reader<T: Borrow<[u8]>> {
+    data: T,
+    position: usize,
+}
+
+impl<T: Borrow<[u8]>> Reader<T> {
+    /// Constructs a reader starting at the beginning of a buffer.
+    pub fn new(buffer: T) -> Reader<T> {
+        Reader { data: buffer, position: 0 }
+    }
+
+    /// Returns the number of read bits.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the buffer.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Reads a bit.
+    ///
+    /// Returns `None` (and does nothing) if the buffer is exhausted.
+    pub fn read_bit(&mut self) -> Option<bool> {
+        let byte = *self.data.borrow().get(self.position / 8)?;
+        let x = byte & 1 << (7 - self.position % 8) != 0;
+        self.position += 1;
+        Some(x)
+    }
+
+    /// Reads a sequence of bits.
+    ///
+    /// Returns `None` if the buffer ends before the sequence, in which case the sequence is only
+    /// partially read.
+    pub fn read_bits(&mut self, xs: &mut [bool]) -> Option<()> {
+        for x in xs {
+            *x = self.read_bit()?;
+        }
+        Some(())
+    }
+
+    /// Reads `n` bits and appends them to `x`.
+    fn read_int(&mut self, mut x: usize, n: usize) -> Option<usize> {
+        for _ in 0 .. n {
+            x = 2 * x + self.read_bit()? as usize;
+        }
+        Some(x)
+    }
+
+    /// Reads the Elias gamma code of a positive integer.
+    pub fn read_gamma(&mut self) -> Option<usize> {
+        let mut n = 0;
+        while !self.read_bit()? {
+            n += 1;
+            if n == usize::BITS as usize {
+                return None;
+            }
+        }
+        self.read_int(1, n)
+    }
+
+    /// Reads the Elias delta code of a positive integer.
+    pub fn read_delta(&mut self) -> Option<usize> {
+        let n = self.read_gamma()? - 1;
+        if n >= usize::BITS as usize {
+            return None;
+        }
+        self.read_int(1, n)
+    }
+
+    /// Reads the Elias omega code of a positive integer.
+    pub fn read_omega(&mut self) -> Option<usize> {
+        let mut x = 1;
+        while self.read_bit()? {
+            if x >= usize::BITS as usize {
+                return None;
+            }
+            x = self.read_int(1, x)?;
+        }
+        Some(x)
+    }
+
+    /// Reads the Fibonacci code of a positive integer.
+    pub fn read_fibonacci(&mut self) -> Option<usize> {
+        let mut x = 0usize;
+        // The current Fibonacci number, or None if it overflows.
+        let (mut a, mut b) = (Some(1usize), Some(2usize));
+        let mut previous = false;
+        loop {
+            let bit = self.read_bit()?;
+            if bit && previous {
+                return Some(x);
+            }
+            if bit {
+                x = x.checked_add(a?)?;
+            }
+            // Once the Fibonacci number overflows, only the terminating bit is valid.
+            let _ = a?;
+            previous = bit;
+            (a, b) = (b, a.zip(b).and_then(|(a, b)| a.checked_add(b)));
+        }
+    }
+}
+
+#[cfg(test)]
+fn parse(code: &str) -> std::vec::Vec<bool> {
+    code.bytes().map(|x| x == b'1').collect()
+}
+
+#[cfg(test)]
+fn check(x: usize, code: &str, len: usize, write: fn(&mut Writer<&mut [u8]>, usize)) {
+    let code = parse(code);
+    assert_eq!(len, code.len(), "x={x}");
+    let mut buffer = [0xa5u8; 32];
+    let mut writer = Writer::new(&mut buffer[..]);
+    write(&mut writer, x);
+    assert_eq!(writer.position(), code.len(), "x={x}");
+    let mut reader = Reader::new(&buffer[..]);
+    let mut ys = std::vec![false; code.len()];
+    assert_eq!(reader.read_bits(&mut ys), Some(()));
+    assert_eq!(ys, code, "x={x}");
+}
+
+#[cfg(test)]
+fn round_trip(
+    len: fn(usize) -> usize,
+    write: fn(&mut Writer<&mut [u8]>, usize),
+    read: fn(&mut Reader<&[u8]>) -> Option<usize>,
+) {
+    let xs = (1 .. 1000).chain([usize::MAX / 2, usize::MAX / 2 + 1, usize::MAX - 1, usize::MAX]);
+    let mut buffer = std::vec![0u8; 1 << 16];
+    for x in xs.clone() {
+        let mut writer = Writer::new(&mut buffer[..]);
+        write(&mut writer, x);
+        assert_eq!(writer.position(), len(x), "x={x}");
+        let mut reader = Reader::new(&buffer[..]);
+        assert_eq!(read(&mut reader), Some(x), "x={x}");
+        assert_eq!(reader.position(), len(x), "x={x}");
+        let mut reader = Reader::new(&buffer[.. (len(x) - 1) / 8]);
+        assert_eq!(read(&mut reader), None, "x={x}");
+    }
+    let mut writer = Writer::new(&mut buffer[..]);
+    for x in xs.clone() {
+        write(&mut writer, x);
+    }
+    let mut reader = Reader::new(&buffer[..]);
+    for x in xs {
+        assert_eq!(read(&mut reader), Some(x), "x={x}");
+    }
+}
+
+#[test]
+fn gamma_ok() {
+    fn test(x: usize, code: &str) {
+        check(x, code, gamma_len(x), |w, x| w.write_gamma(x));
+    }
+    test(1, "1");
+    test(2, "010");
+    test(3, "011");
+    test(4, "00100");
+    test(7, "00111");
+    test(8, "0001000");
+    round_trip(gamma_len, |w, x| w.write_gamma(x), |r| r.read_gamma());
+    let mut reader = Reader::new(&[0u8; 9][..]);
+    assert_eq!(reader.read_gamma(), None);
+}
+
+#[test]
+fn delta_ok() {
+    fn test(x: usize, code: &str) {
+        check(x, code, delta_len(x), |w, x| w.write_delta(x));
+    }
+    test(1, "1");
+    test(2, "0100");
+    test(3, "0101");
+    test(4, "01100");
+    test(8, "00100000");
+    test(17, "001010001");
+    round_trip(delta_len, |w, x| w.write_delta(x), |r| r.read_delta());
+}
+
+#[test]
+fn omega_ok() {
+    fn test(x: usize, code: &str) {
+        check(x, code, omega_len(x), |w, x| w.write_omega(x));
+    }
+    test(1, "0");
+    test(2, "100");
+    test(3, "110");
+    test(4, "101000");
+    test(7, "101110");
+    test(8, "1110000");
+    test(16, "10100100000");
+    test(100, "1011011001000");
+    round_trip(omega_len, |w, x| w.write_omega(x), |r| r.read_omega());
+}
+
+#[test]
+fn fibonacci_ok() {
+    fn test(x: usize, code: &str) {
+        check(x, code, fibonacci_len(x), |w, x| w.write_fibonacci(x));
+    }
+    test(1, "11");
+    test(2, "011");
+    test(3, "0011");
+    test(4, "1011");
+    test(5, "00011");
+    test(6, "10011");
+    test(7, "01011");
+    test(8, "000011");
+    test(11, "001011");
+    test(12, "101011");
+    round_trip(fibonacci_len, |w, x| w.write_fibonacci(x), |r| r.read_fibonacci());
+    let mut reader = Reader::new(&[0x55u8; 32][..]);
+    assert_eq!(reader.read_fibonacci(), None);
+}
@@ -0,0 +1,346 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Zeckendorf number system
+//!
+//! This permits to convert between values and their Zeckendorf representation, which writes each
+//! value as a sum of non-consecutive Fibonacci numbers. Representations are bit sequences (i.e.
+//! `[bool]`) without consecutive ones, most significant bit first. The bit at index `i` of a
+//! sequence of length `n` stands for the Fibonacci number at index `n + 1 - i` (where the
+//! Fibonacci numbers at index 2 and 3 are 1 and 2).
+//!
+//! The functions suffixed with `_run_limited` generalize it to bit sequences of a given length
+//! without `k` consecutive ones, ordered lexicographically. The Zeckendorf functions are the
+//! special case `k = 2`, up to leading zeros. There are [`run_limited(n, k)`](crate::run_limited)
+//! such sequences of length `n`.
+//!
+//! See [wikipedia] for more information.
+//!
+//! [wikipedia]: https://en.wikipedia.org/wiki/Zeckendorf%27s_theorem
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::RunLimited;
+
+/// Returns whether a bit sequence has no `k` consecutive ones.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::is_run_limited;
+/// assert!(is_run_limited(&[true, false, true], 2));
+/// assert!(!is_run_limited(&[false, true, true], 2));
+/// assert!(is_run_limited(&[false, true, true], 3));
+/// ```
+pub fn is_run_limited(xs: &[bool], k: usize) -> bool {
+    let mut run = 0;
+    for &x in xs {
+        run = if x { run + 1 } else { 0 };
+        if run >= k {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns the length of the Zeckendorf representation of a value.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::decode_len;
+/// assert_eq!(decode_len(0), 0);
+/// assert_eq!(decode_len(1), 1);
+/// assert_eq!(decode_len(2), 2);
+/// assert_eq!(decode_len(3), 3);
+/// assert_eq!(decode_len(4), 3);
+/// assert_eq!(decode_len(5), 4);
+/// ```
+pub fn decode_len(s: usize) -> usize {
+    let (mut a, mut b) = (1usize, 2usize);
+    let mut n = 0;
+    while a <= s {
+        n += 1;
+        match a.checked_add(b) {
+            Some(c) => (a, b) = (b, c),
+            None if b <= s => return n + 1,
+            None => break,
+        }
+    }
+    n
+}
+
+#[test]
+fn decode_len_ok() {
+    fn test(s: usize, n: usize) {
+        assert_eq!(decode_len(s), n, "s={s}");
+    }
+    test(0, 0);
+    test(1, 1);
+    test(2, 2);
+    test(3, 3);
+    test(4, 3);
+    test(5, 4);
+    test(7, 4);
+    test(8, 5);
+    test(12, 5);
+    test(13, 6);
+    for s in 0 .. 1000 {
+        let n = decode_len(s);
+        assert!(crate::run_limited(n, 2) > s, "s={s}");
+        assert!(n == 0 || crate::run_limited(n - 1, 2) <= s, "s={s}");
+    }
+    assert!(decode_len(usize::MAX) < RunLimited::LEN);
+}
+
+/// Writes the Zeckendorf representation of a value to a slice.
+///
+/// The written representation can be encoded with [`encode`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::{decode_len, decode_mut, encode};
+/// # let s = 13;
+/// let n = decode_len(s);
+/// let mut xs = vec![false; n];
+/// decode_mut(s, &mut xs);
+/// assert_eq!(encode(&xs), s);
+/// ```
+///
+/// See [`decode`] for a version that allocates a vector for the representation.
+///
+/// This is the special case of [`decode_run_limited_mut`] with `k = 2`.
+///
+/// # Panics
+///
+/// Panics in debug mode if `xs.len() != decode_len(s)`.
+pub fn decode_mut(s: usize, xs: &mut [bool]) {
+    debug_assert_eq!(xs.len(), decode_len(s), "Failed precondition");
+    decode_run_limited_mut(s, 2, xs);
+}
+
+/// Returns the Zeckendorf representation of a value.
+///
+/// The returned representation can be encoded with [`encode`] to get back `s`.
+///
+/// See [`decode_mut`] for a version that writes the representation to a provided slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::decode;
+/// assert_eq!(decode(0), &[]);
+/// assert_eq!(decode(1), &[true]);
+/// assert_eq!(decode(2), &[true, false]);
+/// assert_eq!(decode(4), &[true, false, true]);
+/// assert_eq!(decode(12), &[true, false, true, false, true]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode(s: usize) -> Vec<bool> {
+    let mut xs = vec![false; decode_len(s)];
+    decode_mut(s, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_ok() {
+    fn test(s: usize, r: &[bool]) {
+        assert_eq!(decode(s), r, "s={s}");
+    }
+    test(0, &[]);
+    test(1, &[true]);
+    test(2, &[true, false]);
+    test(3, &[true, false, false]);
+    test(4, &[true, false, true]);
+    test(5, &[true, false, false, false]);
+    test(6, &[true, false, false, true]);
+    test(7, &[true, false, true, false]);
+    test(8, &[true, false, false, false, false]);
+    for s in (0 .. 1000).chain(usize::MAX - 1000 ..= usize::MAX) {
+        let xs = decode(s);
+        assert_eq!(xs.first(), (s > 0).then_some(&true), "s={s}");
+        assert!(is_run_limited(&xs, 2), "s={s}");
+        assert_eq!(encode(&xs), s, "s={s}");
+    }
+}
+
+/// Returns the value of a Zeckendorf representation.
+///
+/// The returned value can be decoded with [`decode`] to get back `xs` without its leading zeros.
+///
+/// This is the special case of [`encode_run_limited`] with `k = 2`.
+///
+/// # Panics
+///
+/// Panics if the value overflows. Panics in debug mode if `xs` has consecutive ones.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::encode;
+/// assert_eq!(encode(&[]), 0);
+/// assert_eq!(encode(&[true]), 1);
+/// assert_eq!(encode(&[true, false, true]), 4);
+/// assert_eq!(encode(&[false, true, false, true]), 4);
+/// ```
+pub fn encode(xs: &[bool]) -> usize {
+    encode_run_limited(xs, 2)
+}
+
+#[test]
+fn encode_ok() {
+    fn test(xs: &[bool], s: usize) {
+        assert_eq!(encode(xs), s, "xs={xs:?}");
+    }
+    test(&[], 0);
+    test(&[false], 0);
+    test(&[true], 1);
+    test(&[true, false], 2);
+    test(&[false, true], 1);
+    test(&[true, false, true, false, true], 12);
+    let mut xs = vec![false; 200];
+    xs[199] = true;
+    xs[197] = true;
+    test(&xs, 4);
+}
+
+/// Writes the bit sequence of a value to a slice.
+///
+/// The written sequence has no `k` consecutive ones and can be encoded with
+/// [`encode_run_limited`] to get back `s`.
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::{decode_run_limited_mut, encode_run_limited};
+/// # let s = 13;
+/// # let k = 3;
+/// let mut xs = vec![false; 6];
+/// decode_run_limited_mut(s, k, &mut xs);
+/// assert_eq!(encode_run_limited(&xs, k), s);
+/// ```
+///
+/// See [`decode_run_limited`] for a version that allocates a vector for the sequence.
+///
+/// # Panics
+///
+/// Panics in debug mode if `k == 0` or `s >= run_limited(xs.len(), k)`.
+pub fn decode_run_limited_mut(mut s: usize, k: usize, xs: &mut [bool]) {
+    let counts = RunLimited::new(k);
+    let n = xs.len();
+    debug_assert!(counts.get(n).is_none_or(|c| s < c), "Failed precondition");
+    for (i, x) in xs.iter_mut().enumerate() {
+        match counts.get(n - 1 - i) {
+            Some(c) if c <= s => {
+                *x = true;
+                s -= c;
+            }
+            _ => *x = false,
+        }
+    }
+}
+
+/// Returns the bit sequence of length `n` of a value.
+///
+/// The returned sequence has no `k` consecutive ones and can be encoded with
+/// [`encode_run_limited`] to get back `s`.
+///
+/// See [`decode_run_limited_mut`] for a version that writes the sequence to a provided slice.
+///
+/// # Panics
+///
+/// Panics in debug mode if `k == 0` or `s >= run_limited(n, k)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::decode_run_limited;
+/// assert_eq!(decode_run_limited(0, 3, 3), &[false, false, false]);
+/// assert_eq!(decode_run_limited(3, 3, 3), &[false, true, true]);
+/// assert_eq!(decode_run_limited(4, 3, 3), &[true, false, false]);
+/// assert_eq!(decode_run_limited(6, 3, 3), &[true, true, false]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_run_limited(s: usize, n: usize, k: usize) -> Vec<bool> {
+    let mut xs = vec![false; n];
+    decode_run_limited_mut(s, k, &mut xs);
+    xs
+}
+
+#[test]
+fn decode_run_limited_ok() {
+    for n in 0 .. 10 {
+        for k in 1 .. 12 {
+            let mut expected = (0 .. 1usize << n)
+                .map(|x| (0 .. n).map(|i| x & 1 << (n - 1 - i) != 0).collect::<Vec<_>>())
+                .filter(|xs| is_run_limited(xs, k));
+            for s in 0 .. crate::run_limited(n, k) {
+                assert_eq!(Some(decode_run_limited(s, n, k)), expected.next(), "s={s}");
+            }
+            assert_eq!(expected.next(), None);
+        }
+    }
+    let xs = decode_run_limited(5, 1000, 3);
+    assert!(xs[.. 997].iter().all(|&x| !x));
+    assert_eq!(xs[997 ..], [true, false, true]);
+    assert_eq!(decode_run_limited(0, 1000, 1), [false; 1000]);
+}
+
+/// Returns the value of a bit sequence without `k` consecutive ones.
+///
+/// The returned value can be decoded with [`decode_run_limited`] to get back `xs`.
+///
+/// # Panics
+///
+/// Panics if the value overflows. Panics in debug mode if `xs` has `k` consecutive ones.
+///
+/// # Examples
+///
+/// ```rust
+/// # use number_encoding::zeckendorf::encode_run_limited;
+/// assert_eq!(encode_run_limited(&[false, false, false], 3), 0);
+/// assert_eq!(encode_run_limited(&[false, true, true], 3), 3);
+/// assert_eq!(encode_run_limited(&[true, false, false], 3), 4);
+/// assert_eq!(encode_run_limited(&[true, true, false], 3), 6);
+/// ```
+pub fn encode_run_limited(xs: &[bool], k: usize) -> usize {
+    debug_assert!(is_run_limited(xs, k), "Failed precondition");
+    let counts = RunLimited::new(k);
+    let n = xs.len();
+    let mut s = 0usize;
+    for (i, &x) in xs.iter().enumerate() {
+        if x {
+            s = counts.get(n - 1 - i).and_then(|c| s.checked_add(c)).expect("Overflow");
+        }
+    }
+    s
+}
+
+#[test]
+fn encode_run_limited_ok() {
+    for n in 0 .. 10 {
+        for k in 1 .. 12 {
+            for s in 0 .. crate::run_limited(n, k) {
+                assert_eq!(encode_run_limited(&decode_run_limited(s, n, k), k), s);
+            }
+        }
+    }
+    for s in [0, 1, usize::MAX / 3, usize::MAX] {
+        for k in [2, 3, 10] {
+            let xs = decode_run_limited(s, 200, k);
+            assert!(is_run_limited(&xs, k));
+            assert_eq!(encode_run_limited(&xs, k), s);
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use number_encoding::{combinadics, factoradics, factorial, multinadics, multinomial};
+use number_encoding::{
+    bracelet, combinadics, combination, factoradics, factorial, gaussadics, gaussian_binomial,
+    lyndon, multinadics, multinomial, necklace, necklaces, run_limited,
+};
 
 #[test]
 fn factoradics_bijective() {
@@ -61,3 +64,101 @@ fn factoradics_is_multinadics() {
         }
     }
 }
+
+#[test]
+fn gaussadics_is_combinadics() {
+    for n in 0 ..= 9 {
+        for k in 0 ..= n {
+            let counts = gaussian_binomial(n, k);
+            let mut seen = vec![0; counts.len()];
+            let mut iter = combinadics::Iter::new(k);
+            for _ in 0 .. combination(n, k) {
+                let xs = iter.get();
+                let w = gaussadics::weight(xs);
+                let p = gaussadics::encode(xs);
+                assert!(p < counts[w], "xs={xs:?}");
+                assert_eq!(gaussadics::decode(p, k, w), xs);
+                seen[w] += 1;
+                iter.advance();
+            }
+            assert_eq!(seen, counts);
+            for (w, &c) in counts.iter().enumerate() {
+                let mut iter = gaussadics::Iter::new(k, w);
+                for p in 0 .. c {
+                    let xs = iter.next().unwrap();
+                    assert!(xs.last().is_none_or(|&x| x < n));
+                    assert_eq!(gaussadics::encode(xs), p);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn necklaces_bijective() {
+    for n in 0 ..= 6 {
+        for k in 0 ..= 3 {
+            let mut words = vec![vec![]];
+            for _ in 0 .. n {
+                words = words
+                    .into_iter()
+                    .flat_map(|w| (0 .. k).map(move |c| [w.clone(), vec![c]].concat()))
+                    .collect();
+            }
+            let mut all = Vec::new();
+            let mut free = Vec::new();
+            for w in &words {
+                let mut x = w.clone();
+                necklaces::canonicalize(&mut x);
+                all.push(x);
+                let mut x = w.clone();
+                necklaces::canonicalize_bracelet(&mut x);
+                free.push(x);
+            }
+            all.sort();
+            all.dedup();
+            free.sort();
+            free.dedup();
+            let prim: Vec<_> = all.iter().filter(|x| necklaces::is_lyndon(x)).collect();
+            assert_eq!(all.len(), necklace(n, k), "n={n} k={k}");
+            assert_eq!(prim.len(), lyndon(n, k), "n={n} k={k}");
+            assert_eq!(free.len(), bracelet(n, k), "n={n} k={k}");
+            for (p, x) in all.iter().enumerate() {
+                assert_eq!(necklaces::encode(x, k), p);
+                assert_eq!(&necklaces::decode(p, n, k), x);
+            }
+            for (p, x) in prim.iter().enumerate() {
+                assert_eq!(necklaces::encode_lyndon(x, k), p);
+                assert_eq!(&&necklaces::decode_lyndon(p, n, k), x);
+            }
+            for (p, x) in free.iter().enumerate() {
+                assert!(necklaces::is_bracelet(x));
+                assert_eq!(necklaces::encode_bracelet(x, k), p);
+                assert_eq!(&necklaces::decode_bracelet(p, n, k), x);
+            }
+        }
+    }
+}
+
+#[test]
+fn zeckendorf_is_run_limited() {
+    use number_encoding::zeckendorf;
+    for n in 0 ..= 12 {
+        for s in 0 .. run_limited(n, 2) {
+            let xs = zeckendorf::decode_run_limited(s, n, 2);
+            let ys = zeckendorf::decode(s);
+            assert_eq!(xs[n - ys.len() ..], ys);
+            assert!(xs[.. n - ys.len()].iter().all(|&x| !x));
+        }
+    }
+}
+
+#[test]
+fn pairs_is_combinadics() {
+    use number_encoding::pairs;
+    for r in 0 .. combination(200, 2) {
+        let (i, j) = pairs::decode(r);
+        assert_eq!(combinadics::decode(r, 2), [i, j]);
+        assert_eq!(combinadics::encode(&[i, j]), r);
+    }
+}